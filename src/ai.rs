@@ -1,3 +1,12 @@
+use anyhow::{Result, anyhow};
+
+/// Analyses offered in the command palette, as (id, title).
+pub const AI_ACTIONS: [(&str, &str); 3] = [
+    ("summarize", "Summarize the desk"),
+    ("explain_funding", "Explain funding dislocations"),
+    ("rank_carry", "Review top carry opportunities"),
+];
+
 #[derive(Debug, Default)]
 pub struct AiOrchestrator {
     // Placeholder for AI agent coordination (LLM clients, MCP adapters, etc.)
@@ -11,4 +20,13 @@ impl AiOrchestrator {
     pub fn readiness_label(&self) -> &'static str {
         "AI offline"
     }
+
+    /// Runs one of `AI_ACTIONS`, returning the agent's answer.
+    pub fn run(&self, action: &str) -> Result<String> {
+        let (_, title) = AI_ACTIONS
+            .iter()
+            .find(|(id, _)| *id == action)
+            .ok_or_else(|| anyhow!("unknown AI action {:?}", action))?;
+        Err(anyhow!("{}: no AI agent configured", title))
+    }
 }
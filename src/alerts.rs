@@ -1,11 +1,23 @@
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow};
 use chrono::{DateTime, Utc};
 
+use crate::config::AlertConfig;
+use crate::metrics::{InstrumentMetrics, MetricsSummary, StatsWindow};
+use crate::vol::VolSurface;
+
 #[derive(Debug, Clone)]
 pub struct AlertStatus {
     pub name: String,
     pub is_triggered: bool,
     pub threshold: String,
     pub last_triggered: Option<DateTime<Utc>>,
+    pub rule: Option<AlertRule>,
+    /// Still evaluated, but never counted as firing.
+    pub muted: bool,
+    /// Set by the user on a firing alert; cleared when it fires again.
+    pub acknowledged: bool,
 }
 
 impl AlertStatus {
@@ -15,10 +27,278 @@ impl AlertStatus {
             is_triggered: false,
             threshold: threshold.into(),
             last_triggered: None,
+            rule: None,
+            muted: false,
+            acknowledged: false,
+        }
+    }
+
+    /// Triggered, unmuted and not yet acknowledged.
+    pub fn needs_attention(&self) -> bool {
+        self.is_triggered && !self.muted && !self.acknowledged
+    }
+
+    pub fn from_config(config: &AlertConfig) -> Self {
+        match config.condition.parse::<AlertCondition>() {
+            Ok(condition) => Self {
+                rule: Some(AlertRule {
+                    asset: config.asset.clone(),
+                    venue: config.venue.clone(),
+                    symbol: config.symbol.clone(),
+                    condition,
+                }),
+                ..Self::placeholder(&config.name, &config.condition)
+            },
+            Err(err) => Self::placeholder(
+                &config.name,
+                format!("{} (invalid: {})", config.condition, err),
+            ),
+        }
+    }
+}
+
+/// Which instruments an alert watches and the condition it fires on. Unset
+/// asset/venue/symbol filters match every instrument.
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub asset: Option<String>,
+    pub venue: Option<String>,
+    pub symbol: Option<String>,
+    pub condition: AlertCondition,
+}
+
+impl AlertRule {
+    fn is_met(&self, summary: &MetricsSummary) -> bool {
+        if self.condition.metric.is_surface_metric() {
+            return summary
+                .assets
+                .iter()
+                .filter(|asset| self.matches_asset(&asset.asset))
+                .filter_map(|asset| asset.vol.as_ref())
+                .any(|vol| {
+                    self.venue.as_ref().is_none_or(|venue| *venue == vol.venue)
+                        && self
+                            .condition
+                            .is_met_on_surface(vol, self.symbol.as_deref())
+                });
+        }
+
+        summary
+            .instruments
+            .iter()
+            .filter(|metrics| self.matches(metrics))
+            .any(|metrics| self.condition.is_met(metrics))
+    }
+
+    fn matches_asset(&self, asset: &str) -> bool {
+        self.asset
+            .as_ref()
+            .is_none_or(|wanted| wanted.eq_ignore_ascii_case(asset))
+    }
+
+    fn matches(&self, metrics: &InstrumentMetrics) -> bool {
+        self.matches_asset(&metrics.asset)
+            && self
+                .venue
+                .as_ref()
+                .is_none_or(|venue| *venue == metrics.venue)
+            && self
+                .symbol
+                .as_ref()
+                .is_none_or(|symbol| *symbol == metrics.symbol)
+    }
+}
+
+/// A parsed `<metric> <op> <value>[bps|%]` expression, e.g.
+/// `funding > 75 bps` or `funding_z_7d >= 2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertCondition {
+    pub metric: AlertMetric,
+    pub comparator: Comparator,
+    /// Normalized to the metric's raw units (fractions for rates).
+    pub threshold: f64,
+}
+
+impl AlertCondition {
+    pub fn is_met(&self, metrics: &InstrumentMetrics) -> bool {
+        self.metric
+            .value(metrics)
+            .is_some_and(|value| self.comparator.compare(value, self.threshold))
+    }
+
+    /// Surface metrics read the expiry named by `expiry` (e.g. `27JUN25`),
+    /// defaulting to the front expiry.
+    pub fn is_met_on_surface(&self, vol: &VolSurface, expiry: Option<&str>) -> bool {
+        self.metric
+            .surface_value(vol, expiry)
+            .is_some_and(|value| self.comparator.compare(value, self.threshold))
+    }
+}
+
+impl FromStr for AlertCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let mut tokens = raw.split_whitespace();
+        let metric = tokens
+            .next()
+            .context("missing metric")?
+            .parse::<AlertMetric>()?;
+        let comparator = tokens
+            .next()
+            .context("missing comparator")?
+            .parse::<Comparator>()?;
+        let value: String = tokens.collect();
+        if value.is_empty() {
+            return Err(anyhow!("missing threshold"));
+        }
+
+        let (number, scale) = if let Some(number) = value.strip_suffix("bps") {
+            (number, 10_000.0)
+        } else if let Some(number) = value.strip_suffix('%') {
+            (number, 100.0)
+        } else {
+            (value.as_str(), 1.0)
+        };
+        let threshold = number
+            .trim()
+            .parse::<f64>()
+            .with_context(|| format!("invalid threshold {:?}", value))?;
+
+        Ok(Self {
+            metric,
+            comparator,
+            threshold: threshold / scale,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Above,
+    AtOrAbove,
+    Below,
+    AtOrBelow,
+}
+
+impl Comparator {
+    fn compare(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AtOrAbove => value >= threshold,
+            Self::Below => value < threshold,
+            Self::AtOrBelow => value <= threshold,
+        }
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            ">" => Ok(Self::Above),
+            ">=" => Ok(Self::AtOrAbove),
+            "<" => Ok(Self::Below),
+            "<=" => Ok(Self::AtOrBelow),
+            other => Err(anyhow!("unknown comparator {:?}", other)),
         }
     }
 }
 
+/// Metric inputs an alert can reference. Rate-like metrics are fractions,
+/// so `funding > 75 bps` compares against 0.0075.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMetric {
+    Funding,
+    PredictedFunding,
+    Basis,
+    AnnualizedBasis,
+    Premium,
+    FundingMean(StatsWindow),
+    FundingEwma(StatsWindow),
+    FundingStdDev(StatsWindow),
+    FundingZScore(StatsWindow),
+    FundingPercentile(StatsWindow),
+    VolatilityIndex,
+    AtmIv,
+    RiskReversal25d,
+    Butterfly25d,
+}
+
+impl AlertMetric {
+    /// Vol surface metrics are evaluated against the option chain rather
+    /// than individual instruments.
+    pub fn is_surface_metric(self) -> bool {
+        matches!(
+            self,
+            Self::VolatilityIndex | Self::AtmIv | Self::RiskReversal25d | Self::Butterfly25d
+        )
+    }
+
+    pub fn surface_value(self, vol: &VolSurface, expiry: Option<&str>) -> Option<f64> {
+        match self {
+            Self::VolatilityIndex => vol.volatility_index,
+            Self::AtmIv => vol.expiry(expiry).map(|expiry| expiry.atm_iv),
+            Self::RiskReversal25d => vol.expiry(expiry).and_then(|e| e.risk_reversal_25d),
+            Self::Butterfly25d => vol.expiry(expiry).and_then(|e| e.butterfly_25d),
+            _ => None,
+        }
+    }
+
+    pub fn value(self, metrics: &InstrumentMetrics) -> Option<f64> {
+        match self {
+            Self::Funding => metrics.funding_rate,
+            Self::PredictedFunding => metrics.predicted_funding_rate,
+            Self::Basis => metrics.basis_bps.map(|bps| bps / 10_000.0),
+            Self::AnnualizedBasis => metrics.annualized_basis,
+            Self::Premium => metrics.premium_bps.map(|bps| bps / 10_000.0),
+            Self::FundingMean(window) => metrics.stats(window).map(|stats| stats.mean),
+            Self::FundingEwma(window) => metrics.stats(window).map(|stats| stats.ewma),
+            Self::FundingStdDev(window) => metrics.stats(window).map(|stats| stats.std_dev),
+            Self::FundingZScore(window) => metrics.stats(window).and_then(|stats| stats.z_score),
+            Self::FundingPercentile(window) => metrics.stats(window).map(|stats| stats.percentile),
+            Self::VolatilityIndex | Self::AtmIv | Self::RiskReversal25d | Self::Butterfly25d => {
+                None
+            }
+        }
+    }
+}
+
+impl FromStr for AlertMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let metric = match raw {
+            "funding" => Self::Funding,
+            "predicted_funding" => Self::PredictedFunding,
+            "basis" => Self::Basis,
+            "annualized_basis" => Self::AnnualizedBasis,
+            "premium" => Self::Premium,
+            "dvol" => Self::VolatilityIndex,
+            "atm_iv" => Self::AtmIv,
+            "rr_25d" => Self::RiskReversal25d,
+            "bf_25d" => Self::Butterfly25d,
+            _ => {
+                let (stat, window) = raw
+                    .strip_prefix("funding_")
+                    .and_then(|rest| rest.rsplit_once('_'))
+                    .ok_or_else(|| anyhow!("unknown metric {:?}", raw))?;
+                let window = window.parse::<StatsWindow>().map_err(|err| anyhow!(err))?;
+                match stat {
+                    "mean" => Self::FundingMean(window),
+                    "ewma" => Self::FundingEwma(window),
+                    "std" => Self::FundingStdDev(window),
+                    "z" => Self::FundingZScore(window),
+                    "pctl" => Self::FundingPercentile(window),
+                    _ => return Err(anyhow!("unknown metric {:?}", raw)),
+                }
+            }
+        };
+        Ok(metric)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AlertManager {
     pub alerts: Vec<AlertStatus>,
@@ -29,10 +309,135 @@ impl AlertManager {
         Self::default()
     }
 
+    pub fn from_config(configs: &[AlertConfig]) -> Self {
+        Self {
+            alerts: configs.iter().map(AlertStatus::from_config).collect(),
+        }
+    }
+
+    /// Rebuilds the alerts from a reloaded config. Alerts added this session
+    /// (named in neither config) are kept, and mute, acknowledgement and
+    /// trigger state carry over by name.
+    pub fn reload(&mut self, configs: &[AlertConfig], previous: &[AlertConfig]) {
+        let configured = |name: &str| {
+            configs
+                .iter()
+                .chain(previous)
+                .any(|config| config.name == name)
+        };
+        let mut old = std::mem::take(&mut self.alerts);
+        let session: Vec<AlertStatus> = old
+            .iter()
+            .filter(|alert| !configured(&alert.name))
+            .cloned()
+            .collect();
+        self.alerts = configs.iter().map(AlertStatus::from_config).collect();
+        for alert in &mut self.alerts {
+            if let Some(index) = old.iter().position(|known| known.name == alert.name) {
+                let known = old.swap_remove(index);
+                alert.is_triggered = known.is_triggered;
+                alert.last_triggered = known.last_triggered;
+                alert.muted = known.muted;
+                alert.acknowledged = known.acknowledged;
+            }
+        }
+        self.alerts.extend(session);
+    }
+
+    /// Alerts firing that nobody has muted or acknowledged.
     pub fn triggered_count(&self) -> usize {
         self.alerts
             .iter()
-            .filter(|alert| alert.is_triggered)
+            .filter(|alert| alert.needs_attention())
             .count()
     }
+
+    /// Adds an alert at runtime; fails if its condition does not parse.
+    pub fn add(&mut self, config: &AlertConfig) -> anyhow::Result<()> {
+        config
+            .condition
+            .parse::<AlertCondition>()
+            .with_context(|| format!("invalid alert condition {:?}", config.condition))?;
+        self.alerts.push(AlertStatus::from_config(config));
+        Ok(())
+    }
+
+    /// Flips the named alert's mute flag; returns the new state.
+    pub fn toggle_mute(&mut self, name: &str) -> Option<bool> {
+        let alert = self.alerts.iter_mut().find(|alert| alert.name == name)?;
+        alert.muted = !alert.muted;
+        Some(alert.muted)
+    }
+
+    pub fn acknowledge(&mut self, name: &str) -> bool {
+        match self
+            .alerts
+            .iter_mut()
+            .find(|alert| alert.name == name && alert.is_triggered)
+        {
+            Some(alert) => {
+                alert.acknowledged = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-evaluates every rule against the latest metrics. An alert fires
+    /// when any instrument it watches meets its condition.
+    pub fn evaluate(&mut self, summary: &MetricsSummary) {
+        let now = Utc::now();
+        for alert in &mut self.alerts {
+            let Some(rule) = &alert.rule else {
+                continue;
+            };
+            let triggered = rule.is_met(summary);
+
+            if triggered && !alert.is_triggered {
+                alert.last_triggered = Some(now);
+                alert.acknowledged = false;
+            }
+            alert.is_triggered = triggered;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(name: &str, condition: &str) -> AlertConfig {
+        AlertConfig {
+            name: name.into(),
+            asset: None,
+            venue: None,
+            symbol: None,
+            condition: condition.into(),
+        }
+    }
+
+    #[test]
+    fn reload_keeps_session_alerts_and_flags() {
+        let previous = vec![
+            alert("hot", "funding > 50 bps"),
+            alert("gone", "funding < 0"),
+        ];
+        let mut manager = AlertManager::from_config(&previous);
+        manager.add(&alert("mine", "basis > 10 bps")).unwrap();
+        manager.toggle_mute("hot");
+        manager.toggle_mute("mine");
+
+        let reloaded = vec![
+            alert("hot", "funding > 75 bps"),
+            alert("new", "funding > 1 bps"),
+        ];
+        manager.reload(&reloaded, &previous);
+
+        let names: Vec<&str> = manager.alerts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["hot", "new", "mine"]);
+        assert_eq!(manager.alerts[0].threshold, "funding > 75 bps");
+        assert!(manager.alerts[0].muted);
+        assert!(!manager.alerts[1].muted);
+        assert!(manager.alerts[2].muted);
+    }
 }
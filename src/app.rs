@@ -1,21 +1,65 @@
+use std::cmp::Ordering;
+
 use anyhow::Result;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::ai::AiOrchestrator;
 use crate::alerts::{AlertManager, AlertStatus};
-use crate::config::AppConfig;
-use crate::data::{CollectionOutcome, DataHub, MarketSnapshot};
-use crate::metrics::{MetricsEngine, MetricsSummary};
+use crate::commands::{CommandProvider, CommandRegistry};
+use crate::config::{AlertConfig, AppConfig};
+use crate::data::{CollectionOutcome, DataHub, MarketSnapshot, OptionChain};
+use crate::discovery::{InstrumentInfo, Watchlist};
+use crate::etf::{ETF_VENUE, EtfTracker, EtfValuation};
+use crate::events::{EventKind, EventLog, Severity};
+use crate::forecast::{FundingForecast, FundingForecaster};
+use crate::health::{CircuitBreaker, FeedStats, VenueState};
+use crate::history::SnapshotHistory;
+use crate::keymap::Keymap;
+use crate::layout::{self, WorkspaceConfig};
+use crate::metrics::{InstrumentMetrics, MetricsEngine, MetricsSummary, StatsWindow};
+use crate::persist;
+use crate::scanner::{CarryOpportunity, OpportunityScanner};
+use crate::theme::Theme;
 
 pub struct QuantumDesk {
     pub state: AppState,
     config: AppConfig,
     data_hub: DataHub,
     metrics: MetricsEngine,
+    scanner: OpportunityScanner,
+    forecaster: FundingForecaster,
     alerts: AlertManager,
     ai: AiOrchestrator,
+    option_chains: Vec<OptionChain>,
+    etf: Option<EtfTracker>,
+    watchlist: Watchlist,
+    commands: CommandRegistry,
+    theme: Theme,
+    keymap: Keymap,
+    last_state_save: DateTime<Utc>,
+    /// Start of the latest tick; warnings since then show in the status line.
+    last_tick: DateTime<Utc>,
+    /// Data collected while paused, shown on resume.
+    pending: Option<MarketUpdate>,
+    /// Re-read ETF quotes on the next tick regardless of the cache TTL.
+    force_etf: bool,
+    /// Tick right away instead of waiting out the refresh interval.
+    refresh_requested: bool,
+    /// Re-list venue instruments on the next tick, for an open picker.
+    relist_requested: bool,
 }
 
+/// How often history and the snapshot cache are flushed to disk while running.
+const STATE_SAVE_INTERVAL_MINUTES: i64 = 5;
+/// Most matches the symbol picker and command palette list at once.
+const PICKER_RESULTS: usize = 12;
+const MIN_REFRESH_MS: u64 = 250;
+/// Workspace the `h` key toggles to.
+const HEALTH_WORKSPACE: &str = "health";
+/// Panes that take the navigation keys, in `Tab` order.
+const FOCUSABLE_PANES: [&str; 3] = ["table", "heatmap", "log"];
+
 impl Default for QuantumDesk {
     fn default() -> Self {
         Self::new(AppConfig::default())
@@ -23,139 +67,1221 @@ impl Default for QuantumDesk {
 }
 
 impl QuantumDesk {
-    pub fn new(config: AppConfig) -> Self {
-        let data_hub = DataHub::new();
-        let metrics = MetricsEngine::new();
-        let alerts = AlertManager::new();
+    pub fn new(mut config: AppConfig) -> Self {
+        let started = Utc::now();
+        let mut data_hub = DataHub::new();
+        let mut metrics = MetricsEngine::new(config.realized_vol.clone());
+        let mut events = EventLog::default();
+        if let Some(path) = &config.source {
+            events.push(
+                Severity::Info,
+                EventKind::Config,
+                format!("Loaded config {}", path.display()),
+            );
+        }
+        if let Err(err) = metrics.load_history(&config.history_path()) {
+            events.push(Severity::Error, EventKind::Storage, format!("{:#}", err));
+        }
+        if let Err(err) = data_hub.load_directory(&config.instruments_path()) {
+            events.push(Severity::Error, EventKind::Storage, format!("{:#}", err));
+        }
+        if let Err(err) = data_hub.load_cache(&config.snapshot_cache_path()) {
+            events.push(Severity::Error, EventKind::Storage, format!("{:#}", err));
+        }
+        let watchlist = Watchlist::load(&config.watchlist_path()).unwrap_or_else(|err| {
+            events.push(Severity::Error, EventKind::Storage, format!("{:#}", err));
+            Watchlist::default()
+        });
+        for entry in &watchlist.entries {
+            config.watch(&entry.venue, &entry.symbol);
+        }
+        if config.workspaces.is_empty() {
+            config.workspaces = layout::default_workspaces();
+        }
+        let ui_state = persist::load_json::<UiState>(&config.ui_state_path(), "UI state")
+            .unwrap_or_else(|err| {
+                events.push(Severity::Error, EventKind::Storage, format!("{:#}", err));
+                None
+            })
+            .unwrap_or_default();
+        let scanner = OpportunityScanner::new(config.carry_horizon_hours, config.fee_tier);
+        let mut alerts = AlertManager::from_config(&config.alerts);
         let ai = AiOrchestrator::new();
+        let etf = config.etf.clone().map(EtfTracker::new);
+        let theme = Theme::from_config(&config.theme);
+        let keymap = Keymap::from_config(&config.keymap);
 
-        let market_snapshots = vec![
-            MarketSnapshot::placeholder(
-                "Bitfinex", "Spot", "tBTCUSD", 65_420.12, None, 0.0, None, None,
-            ),
-            MarketSnapshot::placeholder(
-                "Bitfinex",
-                "Perp",
-                "tBTCF0:USTF0",
-                65_410.25,
-                Some(65_430.50),
-                0.00065,
-                Some(0.00072),
-                Some(Utc::now() + Duration::minutes(50)),
-            ),
-            MarketSnapshot::placeholder(
-                "Deribit", "Index", "BTC-USD", 65_398.00, None, 0.0, None, None,
-            ),
-            MarketSnapshot::placeholder(
-                "Deribit",
-                "Perp",
-                "BTC-PERPETUAL",
-                65_402.89,
-                Some(65_420.11),
-                0.00052,
-                Some(0.0006),
-                Some(Utc::now() + Duration::minutes(42)),
-            ),
-        ];
+        let market_snapshots = data_hub.cached_snapshots(&config);
 
-        let mut alert_manager = alerts;
-        alert_manager.alerts = vec![
-            AlertStatus::placeholder("Bitfinex Funding", "> 75 bps"),
-            AlertStatus::placeholder("Deribit Funding", "< -25 bps"),
-            AlertStatus::placeholder("IBIT Premium", "> 1.5%"),
-        ];
+        let metrics_summary = metrics.summarize(&market_snapshots, &[], &config.venues);
+        alerts.evaluate(&metrics_summary);
 
-        let mut state = AppState::new(market_snapshots, alert_manager.alerts.clone());
-        state.metrics_summary = metrics.summarize(&state.market_snapshots);
+        let mut state = AppState::new(market_snapshots, alerts.alerts.clone());
+        state.metrics_summary = metrics_summary;
+        state.stats_window = config.stats_window;
+        state.opportunities = scanner.scan(&state.market_snapshots, &config.venues);
+        state.events = events;
+        state.log_filter = ui_state.log_filter;
+        state.feed_health = data_hub.feed_health();
+        state.table = ui_state.table;
+        state.heatmap = ui_state.heatmap;
+        state.workspace = ui_state
+            .workspace
+            .and_then(|name| {
+                config
+                    .workspaces
+                    .iter()
+                    .position(|workspace| workspace.name == name)
+            })
+            .unwrap_or(0);
 
         let mut desk = Self {
             state,
             config,
             data_hub,
             metrics,
-            alerts: alert_manager,
+            scanner,
+            forecaster: FundingForecaster::new(),
+            alerts,
             ai,
+            option_chains: Vec::new(),
+            etf,
+            watchlist,
+            commands: CommandRegistry::default(),
+            theme,
+            keymap,
+            last_state_save: Utc::now(),
+            last_tick: started,
+            pending: None,
+            force_etf: false,
+            refresh_requested: false,
+            relist_requested: false,
         };
         desk.refresh_status_line();
         desk
     }
 
     pub fn tick(&mut self) -> Result<()> {
+        self.last_tick = Utc::now();
+        let circuits = self.data_hub.status();
         let CollectionOutcome {
-            snapshots,
-            warnings,
+            mut snapshots,
+            options,
+            mut warnings,
         } = self.data_hub.collect(&self.config);
+        if std::mem::take(&mut self.relist_requested) {
+            let listing_warnings = self
+                .data_hub
+                .refresh_directory(&self.config.venues, &self.config.instruments_path());
+            for warning in listing_warnings {
+                self.log(Severity::Warn, EventKind::Fetch, warning);
+            }
+            self.refresh_picker();
+        }
+        self.log_circuit_changes(&circuits);
 
+        let mut update = MarketUpdate::default();
+        if let Some(etf) = &mut self.etf {
+            let ttl = if std::mem::take(&mut self.force_etf) {
+                Duration::zero()
+            } else {
+                Duration::seconds(self.config.cache_ttl_secs as i64)
+            };
+            let (valuations, etf_warnings) = etf.collect(&snapshots, ttl);
+            snapshots.extend(valuations.iter().map(EtfValuation::to_snapshot));
+            warnings.extend(etf_warnings);
+            update.etf_valuations = Some(valuations);
+        }
+
+        for chain in options {
+            self.option_chains
+                .retain(|known| known.venue != chain.venue || known.asset != chain.asset);
+            self.option_chains.push(chain);
+        }
         if !snapshots.is_empty() {
-            self.state.market_snapshots = snapshots;
+            self.metrics.record(&snapshots);
+            update.forecasts = Some(self.forecaster.update(
+                &snapshots,
+                self.metrics.history(),
+                &self.config.venues,
+            ));
+            update.snapshots = Some(snapshots);
+        }
+        for warning in warnings {
+            self.log(Severity::Warn, EventKind::Fetch, warning);
+        }
+        update.feed_health = Some(self.data_hub.feed_health());
+        if Utc::now() - self.last_state_save >= Duration::minutes(STATE_SAVE_INTERVAL_MINUTES) {
+            self.last_state_save = Utc::now();
+            if let Err(err) = self.save_state() {
+                self.log(Severity::Error, EventKind::Storage, format!("{:#}", err));
+            }
+        }
+
+        if self.state.paused_at.is_some() {
+            let pending = self.pending.get_or_insert_with(MarketUpdate::default);
+            pending.absorb(update);
+            let snapshots = pending
+                .snapshots
+                .as_deref()
+                .unwrap_or(&self.state.market_snapshots);
+            // Alerts keep firing on the data held back from the display.
+            let summary =
+                self.metrics
+                    .summarize(snapshots, &self.option_chains, &self.config.venues);
+            self.alerts.evaluate(&summary);
+            self.state.pending_updates = snapshots
+                .iter()
+                .filter(|snapshot| {
+                    !self.state.market_snapshots.iter().any(|shown| {
+                        shown.venue == snapshot.venue
+                            && shown.symbol == snapshot.symbol
+                            && shown.last_updated == snapshot.last_updated
+                    })
+                })
+                .count();
+            self.sync_alerts();
+        } else {
+            self.publish(update);
         }
-        self.state.warnings = warnings;
-        self.state.metrics_summary = self.metrics.summarize(&self.state.market_snapshots);
-        self.state.alerts = self.alerts.alerts.clone();
-        self.refresh_status_line();
         Ok(())
     }
 
+    /// Shows the collected data and re-derives the summary, opportunities
+    /// and alerts from it.
+    fn publish(&mut self, update: MarketUpdate) {
+        if let Some(snapshots) = update.snapshots {
+            self.state.market_snapshots = snapshots;
+        }
+        if let Some(forecasts) = update.forecasts {
+            self.state.forecasts = forecasts;
+        }
+        if let Some(valuations) = update.etf_valuations {
+            self.state.etf_valuations = valuations;
+        }
+        if let Some(feed_health) = update.feed_health {
+            self.state.feed_health = feed_health;
+        }
+        self.state.metrics_summary = self.metrics.summarize(
+            &self.state.market_snapshots,
+            &self.option_chains,
+            &self.config.venues,
+        );
+        self.alerts.evaluate(&self.state.metrics_summary);
+        self.state.opportunities = self
+            .scanner
+            .scan(&self.state.market_snapshots, &self.config.venues);
+        self.sync_alerts();
+    }
+
+    /// Freezes the market panes, or shows what was collected meanwhile.
+    /// Collection, alerts and the log keep running while paused.
+    pub fn toggle_pause(&mut self) {
+        self.state.paused_at = match self.state.paused_at {
+            Some(_) => None,
+            None => Some(Utc::now()),
+        };
+        if self.state.paused_at.is_some() {
+            self.state.notice = Some("Paused".into());
+            self.refresh_status_line();
+            return;
+        }
+        self.state.notice = Some(format!(
+            "Resumed with {} pending updates",
+            self.state.pending_updates
+        ));
+        self.state.pending_updates = 0;
+        let update = self.pending.take().unwrap_or_default();
+        self.publish(update);
+    }
+
+    /// Venues a refresh can be forced for, ETF quotes included when tracked.
+    pub fn refreshable_venues(&self) -> Vec<String> {
+        let mut venues: Vec<String> = self
+            .config
+            .venues
+            .iter()
+            .map(|venue| venue.name.clone())
+            .collect();
+        if self.etf.is_some() {
+            venues.push(ETF_VENUE.to_string());
+        }
+        venues
+    }
+
+    /// Re-fetches `venue`, or every venue, on the next tick even where the
+    /// cached data is younger than `cache_ttl_secs`.
+    pub fn force_refresh(&mut self, venue: Option<&str>) {
+        let venues = match venue {
+            Some(venue) => vec![venue.to_string()],
+            None => self.refreshable_venues(),
+        };
+        let mut held = Vec::new();
+        for venue in &venues {
+            if venue == ETF_VENUE {
+                self.force_etf = true;
+            } else if let Some(reason) = self.data_hub.force_refresh(venue) {
+                held.push(format!("{} {}", venue, reason));
+            }
+        }
+        self.refresh_requested = true;
+        let target = venue.unwrap_or("all venues");
+        if held.is_empty() {
+            self.log(
+                Severity::Info,
+                EventKind::Fetch,
+                format!("Forced refresh of {}", target),
+            );
+            self.state.notice = Some(format!("Refreshing {}", target));
+        } else {
+            let held = held.join(", ");
+            self.log(
+                Severity::Warn,
+                EventKind::Fetch,
+                format!("Forced refresh of {} held back: {}", target, held),
+            );
+            self.state.notice = Some(format!("Refreshing {}; held back: {}", target, held));
+        }
+    }
+
+    /// Forces a refresh of the selected row's venue.
+    pub fn refresh_selected_venue(&mut self) {
+        if let Some(venue) = self
+            .selected_snapshot()
+            .map(|snapshot| snapshot.venue.clone())
+        {
+            self.force_refresh(Some(&venue));
+        }
+    }
+
+    /// Records an event in the log pane.
+    pub fn log(&mut self, severity: Severity, kind: EventKind, message: impl Into<String>) {
+        self.state.events.push(severity, kind, message);
+    }
+
+    /// Logs venues whose circuit opened, degraded or recovered since
+    /// `before`.
+    fn log_circuit_changes(&mut self, before: &[(String, CircuitBreaker)]) {
+        for (venue, breaker) in self.data_hub.status() {
+            let previous = before
+                .iter()
+                .find(|(name, _)| *name == venue)
+                .map_or(VenueState::Healthy, |(_, breaker)| breaker.state);
+            if previous == breaker.state {
+                continue;
+            }
+            let error = breaker.last_error.as_deref().unwrap_or("unknown error");
+            match breaker.state {
+                VenueState::Open if previous != VenueState::HalfOpen => self.log(
+                    Severity::Error,
+                    EventKind::Connection,
+                    format!(
+                        "{} circuit open after {} failures: {}",
+                        venue, breaker.consecutive_failures, error
+                    ),
+                ),
+                VenueState::Open => self.log(
+                    Severity::Error,
+                    EventKind::Connection,
+                    format!("{} reconnect probe failed: {}", venue, error),
+                ),
+                VenueState::Degraded => self.log(
+                    Severity::Warn,
+                    EventKind::Connection,
+                    format!("{} degraded: {}", venue, error),
+                ),
+                VenueState::Healthy if previous == VenueState::Degraded => self.log(
+                    Severity::Info,
+                    EventKind::Connection,
+                    format!("{} recovered", venue),
+                ),
+                VenueState::Healthy => self.log(
+                    Severity::Info,
+                    EventKind::Connection,
+                    format!("{} reconnected", venue),
+                ),
+                VenueState::HalfOpen => {}
+            }
+        }
+    }
+
+    /// Flushes state that should survive a restart.
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.save_state()
+    }
+
+    /// Attempts every save, so one failing file does not lose the others,
+    /// and reports the first error.
+    fn save_state(&mut self) -> Result<()> {
+        let history = self.metrics.save_history(&self.config.history_path());
+        let cache = self.data_hub.save_cache(&self.config.snapshot_cache_path());
+        let ui_state = UiState {
+            table: self.state.table.clone(),
+            heatmap: self.state.heatmap.clone(),
+            workspace: Some(self.workspace().name.clone()),
+            log_filter: self.state.log_filter,
+        };
+        let ui = persist::save_json(&self.config.ui_state_path(), &ui_state, "UI state");
+        history.and(cache).and(ui)
+    }
+
     pub fn refresh_interval_ms(&self) -> u64 {
         self.config.update_interval_ms
     }
 
+    /// Refresh interval stretched by however long the tightest venue
+    /// budget needs to afford another tick.
+    pub fn next_tick_ms(&self) -> u64 {
+        let delay = self.data_hub.tick_delay().num_milliseconds().max(0) as u64;
+        self.config.update_interval_ms.max(delay)
+    }
+
+    /// Whether a tick was asked for ahead of the refresh interval; clears
+    /// the request.
+    pub fn take_refresh_request(&mut self) -> bool {
+        std::mem::take(&mut self.refresh_requested)
+    }
+
+    /// Changes the refresh interval, never below `MIN_REFRESH_MS`.
+    pub fn set_refresh_interval(&mut self, ms: u64) {
+        self.config.update_interval_ms = ms.max(MIN_REFRESH_MS);
+        self.state.notice = Some(format!(
+            "Refresh interval {}ms",
+            self.config.update_interval_ms
+        ));
+        self.refresh_status_line();
+    }
+
+    /// Re-reads the config file and applies what can change while running:
+    /// alerts, theme, key bindings and workspaces. Alerts added from the
+    /// palette are kept, and mutes and acknowledgements carry over by name.
+    /// The outcome, or the full error, is logged.
+    pub fn reload_config(&mut self) {
+        let path = self
+            .config
+            .source
+            .clone()
+            .unwrap_or_else(AppConfig::default_path);
+        let loaded = match AppConfig::load(Some(&path)) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.log(
+                    Severity::Error,
+                    EventKind::Config,
+                    format!("Reload failed: {:#}", err),
+                );
+                self.state.notice = Some("Config reload failed (see log)".into());
+                self.refresh_status_line();
+                return;
+            }
+        };
+        self.alerts.reload(&loaded.alerts, &self.config.alerts);
+        self.alerts.evaluate(&self.state.metrics_summary);
+        self.theme = Theme::from_config(&loaded.theme);
+        self.keymap = Keymap::from_config(&loaded.keymap);
+        let current = self.workspace().name.clone();
+        self.config.workspaces = if loaded.workspaces.is_empty() {
+            layout::default_workspaces()
+        } else {
+            loaded.workspaces
+        };
+        self.state.workspace = self
+            .config
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.name == current)
+            .unwrap_or(0);
+        self.state.previous_workspace = self.state.workspace;
+        self.config.alerts = loaded.alerts;
+        self.config.theme = loaded.theme;
+        self.config.keymap = loaded.keymap;
+        self.config.source = Some(path.clone());
+        self.log(
+            Severity::Info,
+            EventKind::Config,
+            format!("Reloaded config {}", path.display()),
+        );
+        self.state.notice = Some("Config reloaded".into());
+        self.sync_alerts();
+    }
+
+    pub fn set_compact(&mut self, compact: bool) {
+        self.config.compact_mode = compact;
+        self.refresh_status_line();
+    }
+
+    pub fn carry_horizon_hours(&self) -> i64 {
+        self.scanner.horizon_hours()
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn mouse_enabled(&self) -> bool {
+        self.config.mouse
+    }
+
     pub fn is_compact(&self) -> bool {
         self.config.compact_mode
     }
 
+    /// Steps the stats columns through off → 1d → 7d → 30d → off.
+    pub fn cycle_stats_window(&mut self) {
+        self.state.stats_window = match self.state.stats_window {
+            None => Some(StatsWindow::Day),
+            Some(StatsWindow::Day) => Some(StatsWindow::Week),
+            Some(StatsWindow::Week) => Some(StatsWindow::Month),
+            Some(StatsWindow::Month) => None,
+        };
+    }
+
+    /// Funding Monitor rows after the filter, in the current sort order.
+    /// Rows without a value for the sort column go last either way.
+    pub fn table_rows(&self) -> Vec<&MarketSnapshot> {
+        let view = &self.state.table;
+        let filter = view.filter.to_lowercase();
+        let summary = &self.state.metrics_summary;
+        let mut rows: Vec<&MarketSnapshot> = self
+            .state
+            .market_snapshots
+            .iter()
+            .filter(|snapshot| {
+                filter.is_empty()
+                    || snapshot.venue.to_lowercase().contains(&filter)
+                    || snapshot.symbol.to_lowercase().contains(&filter)
+            })
+            .collect();
+        let metric = |snapshot: &MarketSnapshot, value: fn(&InstrumentMetrics) -> Option<f64>| {
+            summary
+                .instrument(&snapshot.venue, &snapshot.symbol)
+                .and_then(value)
+        };
+        let direction = |ordering: Ordering| {
+            if view.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        rows.sort_by(|a, b| match view.sort {
+            SortColumn::Asset => {
+                let group = |snapshot: &MarketSnapshot| {
+                    summary
+                        .assets
+                        .iter()
+                        .position(|asset| asset.asset == snapshot.asset)
+                        .unwrap_or(usize::MAX)
+                };
+                direction(group(a).cmp(&group(b)))
+            }
+            SortColumn::Venue => direction(a.venue.cmp(&b.venue).then(a.symbol.cmp(&b.symbol))),
+            SortColumn::Instrument => direction(a.symbol.cmp(&b.symbol)),
+            SortColumn::Funding => compare_optional(
+                a.is_perp().then_some(a.funding_rate),
+                b.is_perp().then_some(b.funding_rate),
+                direction,
+            ),
+            SortColumn::Basis => compare_optional(
+                metric(a, |row| row.basis_bps),
+                metric(b, |row| row.basis_bps),
+                direction,
+            ),
+            SortColumn::Annualized => compare_optional(
+                metric(a, |row| row.annualized_basis),
+                metric(b, |row| row.annualized_basis),
+                direction,
+            ),
+            SortColumn::NextFunding => compare_optional(
+                a.next_funding_time.map(|at| at.timestamp() as f64),
+                b.next_funding_time.map(|at| at.timestamp() as f64),
+                direction,
+            ),
+        });
+        rows
+    }
+
+    /// Moves the table selection by `delta` rows, selecting the first row
+    /// when nothing is selected yet.
+    pub fn move_table_selection(&mut self, delta: isize) {
+        let rows = self.table_rows();
+        if rows.is_empty() {
+            return;
+        }
+        let next = match self.state.table.selected_index(&rows) {
+            Some(idx) => idx.saturating_add_signed(delta).min(rows.len() - 1),
+            None => 0,
+        };
+        let selected = (rows[next].venue.clone(), rows[next].symbol.clone());
+        self.state.table.selected = Some(selected);
+    }
+
+    /// Sorts by `column`, flipping the direction when it already is the sort.
+    pub fn sort_table_by(&mut self, column: SortColumn) {
+        let view = &mut self.state.table;
+        if view.sort == column {
+            view.descending = !view.descending;
+        } else {
+            view.sort = column;
+            view.descending = false;
+        }
+    }
+
+    /// Steps the sort through the columns; back at `Asset` rows are grouped
+    /// again.
+    pub fn cycle_table_sort(&mut self) {
+        self.state.table.sort = self.state.table.sort.next();
+    }
+
+    pub fn reverse_table_sort(&mut self) {
+        self.state.table.descending = !self.state.table.descending;
+    }
+
+    pub fn select_instrument(&mut self, venue: &str, symbol: &str) {
+        self.state.table.selected = Some((venue.to_string(), symbol.to_string()));
+    }
+
+    /// The selected row's snapshot, if it is still listed.
+    pub fn selected_snapshot(&self) -> Option<&MarketSnapshot> {
+        let (venue, symbol) = self.state.table.selected.as_ref()?;
+        self.state
+            .market_snapshots
+            .iter()
+            .find(|snapshot| snapshot.venue == *venue && snapshot.symbol == *symbol)
+    }
+
+    /// Opens the detail view for the selected row.
+    pub fn open_detail(&mut self) {
+        self.state.show_detail = self.selected_snapshot().is_some();
+    }
+
+    pub fn close_detail(&mut self) {
+        self.state.show_detail = false;
+    }
+
+    pub fn history(&self) -> &SnapshotHistory {
+        self.metrics.history()
+    }
+
+    /// The moment the market panes show: now, or when they were paused.
+    pub fn display_time(&self) -> DateTime<Utc> {
+        self.state.paused_at.unwrap_or_else(Utc::now)
+    }
+
+    pub fn workspaces(&self) -> &[WorkspaceConfig] {
+        &self.config.workspaces
+    }
+
+    pub fn workspace(&self) -> &WorkspaceConfig {
+        &self.config.workspaces[self.state.workspace]
+    }
+
+    /// The pane navigation keys go to: the focused pane if the workspace
+    /// shows it, else its first focusable pane.
+    pub fn focused_pane(&self) -> &'static str {
+        let panes = self.workspace().layout.pane_names();
+        let visible: Vec<&'static str> = FOCUSABLE_PANES
+            .into_iter()
+            .filter(|pane| panes.contains(pane))
+            .collect();
+        visible
+            .iter()
+            .find(|pane| **pane == self.state.focus)
+            .or(visible.first())
+            .copied()
+            .unwrap_or(FOCUSABLE_PANES[0])
+    }
+
+    /// Whether `pane` has focus and shares the workspace with another
+    /// focusable pane, so the focus is worth showing.
+    pub fn is_focused(&self, pane: &str) -> bool {
+        let panes = self.workspace().layout.pane_names();
+        let contested = FOCUSABLE_PANES
+            .iter()
+            .filter(|candidate| panes.contains(candidate))
+            .count()
+            > 1;
+        contested && self.focused_pane() == pane
+    }
+
+    /// Gives `pane` keyboard focus if it takes it.
+    pub fn focus_pane(&mut self, pane: &str) {
+        if FOCUSABLE_PANES.contains(&pane) {
+            self.state.focus = pane.to_string();
+        }
+    }
+
+    /// Moves focus to the workspace's next focusable pane.
+    pub fn cycle_focus(&mut self) {
+        let panes = self.workspace().layout.pane_names();
+        let visible: Vec<&str> = FOCUSABLE_PANES
+            .into_iter()
+            .filter(|pane| panes.contains(pane))
+            .collect();
+        let current = self.focused_pane();
+        if let Some(idx) = visible.iter().position(|pane| *pane == current) {
+            self.state.focus = visible[(idx + 1) % visible.len()].to_string();
+        }
+    }
+
+    /// Cross-venue matrix of the heatmap metric: configured assets by venues
+    /// with data, one instrument per cell (the perp where there is one).
+    pub fn heatmap(&self) -> Heatmap {
+        let summary = &self.state.metrics_summary;
+        let metric = self.state.heatmap.metric;
+        let venues: Vec<String> = self
+            .config
+            .venues
+            .iter()
+            .map(|venue| venue.name.clone())
+            .filter(|venue| {
+                self.state
+                    .market_snapshots
+                    .iter()
+                    .any(|snapshot| snapshot.venue == *venue)
+            })
+            .collect();
+        let assets: Vec<String> = summary
+            .assets
+            .iter()
+            .map(|asset| asset.asset.clone())
+            .collect();
+        let cells = assets
+            .iter()
+            .map(|asset| {
+                venues
+                    .iter()
+                    .map(|venue| {
+                        let mut candidates: Vec<&MarketSnapshot> = self
+                            .state
+                            .market_snapshots
+                            .iter()
+                            .filter(|snapshot| snapshot.asset == *asset && snapshot.venue == *venue)
+                            .collect();
+                        candidates.sort_by_key(|snapshot| !snapshot.is_perp());
+                        candidates.into_iter().find_map(|snapshot| {
+                            let row = summary.instrument(&snapshot.venue, &snapshot.symbol)?;
+                            let value = match metric {
+                                HeatmapMetric::Funding => row.annualized_funding,
+                                HeatmapMetric::Basis => row.annualized_basis,
+                            }?;
+                            Some(HeatmapCell {
+                                symbol: snapshot.symbol.clone(),
+                                value,
+                            })
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Heatmap {
+            assets,
+            venues,
+            cells,
+        }
+    }
+
+    /// Moves the heatmap cursor, starting at the top-left cell.
+    pub fn move_heatmap_cursor(&mut self, rows: isize, columns: isize) {
+        let heatmap = self.heatmap();
+        if heatmap.assets.is_empty() || heatmap.venues.is_empty() {
+            return;
+        }
+        let (row, column) = match self.state.heatmap.cursor_position(&heatmap) {
+            Some((row, column)) => (
+                row.saturating_add_signed(rows)
+                    .min(heatmap.assets.len() - 1),
+                column
+                    .saturating_add_signed(columns)
+                    .min(heatmap.venues.len() - 1),
+            ),
+            None => (0, 0),
+        };
+        self.state.heatmap.cursor =
+            Some((heatmap.assets[row].clone(), heatmap.venues[column].clone()));
+    }
+
+    pub fn toggle_heatmap_metric(&mut self) {
+        self.state.heatmap.metric = match self.state.heatmap.metric {
+            HeatmapMetric::Funding => HeatmapMetric::Basis,
+            HeatmapMetric::Basis => HeatmapMetric::Funding,
+        };
+    }
+
+    /// Opens the detail view for the instrument under the heatmap cursor.
+    pub fn open_heatmap_cell(&mut self) {
+        let heatmap = self.heatmap();
+        let Some((row, column)) = self.state.heatmap.cursor_position(&heatmap) else {
+            return;
+        };
+        if let Some(cell) = &heatmap.cells[row][column] {
+            self.select_instrument(&heatmap.venues[column], &cell.symbol);
+            self.open_detail();
+        }
+    }
+
+    /// Scrolls the log pane; positive `delta` moves towards newer events.
+    pub fn scroll_log(&mut self, delta: isize) {
+        let shown = self.state.events.filtered(self.state.log_filter).count();
+        self.state.log_scroll = self
+            .state
+            .log_scroll
+            .saturating_add_signed(-delta)
+            .min(shown.saturating_sub(1));
+    }
+
+    /// Steps the log pane's minimum severity, back at the newest event.
+    pub fn cycle_log_filter(&mut self) {
+        self.state.log_filter = self.state.log_filter.next();
+        self.state.log_scroll = 0;
+    }
+
+    /// Switches to the workspace at `index`, ignoring unknown indices.
+    pub fn switch_workspace(&mut self, index: usize) {
+        if index < self.config.workspaces.len() && index != self.state.workspace {
+            self.state.previous_workspace = self.state.workspace;
+            self.state.workspace = index;
+        }
+    }
+
+    /// Flips between the "health" workspace and the one before it.
+    pub fn toggle_health_pane(&mut self) {
+        if self.workspace().name == HEALTH_WORKSPACE {
+            self.switch_workspace(self.state.previous_workspace);
+        } else if let Some(index) = self
+            .config
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.name == HEALTH_WORKSPACE)
+        {
+            self.switch_workspace(index);
+        }
+    }
+
+    /// Adds an alert for this session from `<condition> [@venue]`.
+    pub fn create_alert(&mut self, input: &str) -> Result<()> {
+        let (condition, venue) = match input.split_once('@') {
+            Some((condition, venue)) => (condition.trim(), Some(venue.trim().to_string())),
+            None => (input.trim(), None),
+        };
+        let config = AlertConfig {
+            name: match &venue {
+                Some(venue) => format!("{} {}", venue, condition),
+                None => condition.to_string(),
+            },
+            asset: None,
+            venue,
+            symbol: None,
+            condition: condition.to_string(),
+        };
+        self.alerts.add(&config)?;
+        self.alerts.evaluate(&self.state.metrics_summary);
+        self.state.notice = Some(format!("Alert added: {}", config.name));
+        self.sync_alerts();
+        Ok(())
+    }
+
+    pub fn toggle_alert_mute(&mut self, name: &str) {
+        if let Some(muted) = self.alerts.toggle_mute(name) {
+            let verb = if muted { "Muted" } else { "Unmuted" };
+            self.state.notice = Some(format!("{} {}", verb, name));
+        }
+        self.sync_alerts();
+    }
+
+    pub fn acknowledge_alert(&mut self, name: &str) {
+        if self.alerts.acknowledge(name) {
+            self.state.notice = Some(format!("Acknowledged {}", name));
+        }
+        self.sync_alerts();
+    }
+
+    /// Publishes the alert states, logging the ones that fired or cleared
+    /// since the last sync.
+    fn sync_alerts(&mut self) {
+        let alerts = self.alerts.alerts.clone();
+        for alert in &alerts {
+            let was_triggered = self
+                .state
+                .alerts
+                .iter()
+                .find(|previous| previous.name == alert.name)
+                .is_some_and(|previous| previous.is_triggered);
+            match (was_triggered, alert.is_triggered) {
+                (false, true) => self.log(
+                    if alert.muted {
+                        Severity::Info
+                    } else {
+                        Severity::Warn
+                    },
+                    EventKind::Alert,
+                    format!("{} triggered ({})", alert.name, alert.threshold),
+                ),
+                (true, false) => self.log(
+                    Severity::Info,
+                    EventKind::Alert,
+                    format!("{} cleared", alert.name),
+                ),
+                _ => {}
+            }
+        }
+        self.state.alerts = alerts;
+        self.refresh_status_line();
+    }
+
+    pub fn run_ai_action(&mut self, action: &str) -> Result<()> {
+        let answer = self.ai.run(action)?;
+        self.state.notice = Some(answer);
+        Ok(())
+    }
+
+    /// Adds a module's commands to the palette.
+    pub fn register_commands(&mut self, provider: CommandProvider) {
+        self.commands.register(provider);
+    }
+
+    pub fn open_palette(&mut self) {
+        self.state.palette = Some(CommandPalette::default());
+        self.refresh_palette();
+    }
+
+    pub fn close_palette(&mut self) {
+        self.state.palette = None;
+    }
+
+    /// Re-runs the palette query; while a command waits for its argument
+    /// the query is that argument and matching is paused.
+    pub fn refresh_palette(&mut self) {
+        let Some(palette) = self.state.palette.as_ref() else {
+            return;
+        };
+        if palette.pending.is_some() {
+            return;
+        }
+        let matches = self.commands.search(self, &palette.query, PICKER_RESULTS);
+        if let Some(palette) = self.state.palette.as_mut() {
+            palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+            palette.matches = matches;
+        }
+    }
+
+    /// Runs the highlighted command, first asking for its argument when it
+    /// takes one. Failures are shown as the notice.
+    pub fn confirm_palette(&mut self) {
+        let Some(palette) = self.state.palette.take() else {
+            return;
+        };
+        let (title, input) = match palette.pending {
+            Some((title, _)) => (title, palette.query),
+            None => match palette.matches.into_iter().nth(palette.selected) {
+                Some(title) => (title, String::new()),
+                None => return,
+            },
+        };
+        let Some(command) = self.commands.find(self, &title) else {
+            return;
+        };
+        if input.is_empty()
+            && let Some(prompt) = command.prompt
+        {
+            self.state.palette = Some(CommandPalette {
+                pending: Some((title, prompt)),
+                ..CommandPalette::default()
+            });
+            return;
+        }
+        if let Err(err) = (command.action)(self, &input) {
+            self.state.notice = Some(format!("{}: {:#}", title, err));
+        }
+        self.refresh_status_line();
+    }
+
+    /// Opens the instrument picker on the local directory; a missing or
+    /// stale one is re-listed on the next tick and the matches follow.
+    pub fn open_symbol_picker(&mut self) {
+        if self.data_hub.directory().is_stale() {
+            self.relist_requested = true;
+            self.state.notice = Some("Refreshing instrument listings".into());
+        }
+        self.state.picker = Some(SymbolPicker::default());
+        self.refresh_picker();
+    }
+
+    pub fn close_symbol_picker(&mut self) {
+        self.state.picker = None;
+    }
+
+    /// Re-runs the picker query against the directory.
+    pub fn refresh_picker(&mut self) {
+        let Some(picker) = self.state.picker.as_mut() else {
+            return;
+        };
+        picker.matches = self
+            .data_hub
+            .directory()
+            .search(&picker.query)
+            .into_iter()
+            .take(PICKER_RESULTS)
+            .cloned()
+            .collect();
+        picker.selected = picker.selected.min(picker.matches.len().saturating_sub(1));
+    }
+
+    /// Adds the highlighted instrument to the watchlist; it is fetched from
+    /// the next tick on.
+    pub fn confirm_symbol_picker(&mut self) {
+        let Some(info) = self
+            .state
+            .picker
+            .take()
+            .and_then(|picker| picker.matches.into_iter().nth(picker.selected))
+        else {
+            return;
+        };
+        let added = self.config.watch(&info.venue, &info.symbol);
+        self.state.notice = Some(if added {
+            format!("Watching {} {}", info.venue, info.symbol)
+        } else {
+            format!("{} {} already tracked", info.venue, info.symbol)
+        });
+        if added
+            && self.watchlist.add(&info.venue, &info.symbol)
+            && let Err(err) = self.watchlist.save(&self.config.watchlist_path())
+        {
+            self.log(Severity::Error, EventKind::Storage, format!("{:#}", err));
+        }
+    }
+
     fn refresh_status_line(&mut self) {
         let mut parts = vec![
             format!(
                 "Mode {}",
                 if self.is_compact() { "compact" } else { "full" }
             ),
-            format!("Refresh {}ms", self.config.update_interval_ms),
+            match self.next_tick_ms() {
+                ms if ms > self.config.update_interval_ms => {
+                    format!("Refresh {}ms (throttled)", ms)
+                }
+                ms => format!("Refresh {}ms", ms),
+            },
             format!("Cache {}s", self.config.cache_ttl_secs),
-            format!("Feed {}", self.data_hub.status()),
+            format!(
+                "Feed {}",
+                self.data_hub
+                    .status()
+                    .iter()
+                    .map(|(venue, breaker)| match breaker.retry_at {
+                        Some(at) if breaker.state == VenueState::Open => format!(
+                            "{} {} {}s",
+                            venue,
+                            breaker.state,
+                            (at - Utc::now()).num_seconds().max(0)
+                        ),
+                        _ => format!("{} {}", venue, breaker.state),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            format!(
+                "Budget {}",
+                self.data_hub
+                    .rate_budget()
+                    .iter()
+                    .map(|(venue, share)| format!("{} {:.0}%", venue, share * 100.0))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
             format!("AI {}", self.ai.readiness_label()),
             format!("Alerts {}", self.alerts.triggered_count()),
         ];
 
-        if let Some(warning) = summarize_warnings(&self.state.warnings) {
-            parts.push(warning);
-        } else {
-            parts.push("Feeds healthy".into());
+        if let Some(notice) = &self.state.notice {
+            parts.push(notice.clone());
+        }
+        match self
+            .state
+            .events
+            .count_since(Severity::Warn, self.last_tick)
+        {
+            0 => parts.push("Feeds healthy".into()),
+            1 => parts.push("1 warning in log".into()),
+            count => parts.push(format!("{} warnings in log", count)),
         }
 
         self.state.status_line = parts.join(" | ");
     }
 }
 
-fn summarize_warnings(warnings: &[String]) -> Option<String> {
-    if warnings.is_empty() {
-        return None;
+/// Market data collected in one tick; parts a tick did not produce are
+/// `None`.
+#[derive(Debug, Default)]
+struct MarketUpdate {
+    snapshots: Option<Vec<MarketSnapshot>>,
+    forecasts: Option<Vec<FundingForecast>>,
+    etf_valuations: Option<Vec<EtfValuation>>,
+    feed_health: Option<Vec<FeedStats>>,
+}
+
+impl MarketUpdate {
+    /// Takes the parts `newer` has, keeping the rest.
+    fn absorb(&mut self, newer: MarketUpdate) {
+        self.snapshots = newer.snapshots.or(self.snapshots.take());
+        self.forecasts = newer.forecasts.or(self.forecasts.take());
+        self.etf_valuations = newer.etf_valuations.or(self.etf_valuations.take());
+        self.feed_health = newer.feed_health.or(self.feed_health.take());
+    }
+}
+
+fn compare_optional(
+    a: Option<f64>,
+    b: Option<f64>,
+    direction: impl Fn(Ordering) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => direction(a.total_cmp(&b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
     }
+}
+
+/// UI settings restored on the next start.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct UiState {
+    table: TableView,
+    heatmap: HeatmapView,
+    workspace: Option<String>,
+    log_filter: Severity,
+}
 
-    let count = warnings.len();
-    let suffix = if count > 1 { "warnings" } else { "warning" };
-    let preview = warnings
-        .first()
-        .map(|warning| truncate_for_status(warning))
-        .unwrap_or_default();
-    Some(format!("{} {}: {}", count, suffix, preview))
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    /// Grouped by asset in summary order.
+    #[default]
+    Asset,
+    Venue,
+    Instrument,
+    Funding,
+    Basis,
+    Annualized,
+    NextFunding,
 }
 
-fn truncate_for_status(text: &str) -> String {
-    const MAX_LEN: usize = 80;
-    if text.len() <= MAX_LEN {
-        return text.to_string();
+impl SortColumn {
+    pub const ALL: [SortColumn; 7] = [
+        SortColumn::Asset,
+        SortColumn::Venue,
+        SortColumn::Instrument,
+        SortColumn::Funding,
+        SortColumn::Basis,
+        SortColumn::Annualized,
+        SortColumn::NextFunding,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortColumn::Asset => "asset",
+            SortColumn::Venue => "venue",
+            SortColumn::Instrument => "instrument",
+            SortColumn::Funding => "funding",
+            SortColumn::Basis => "basis",
+            SortColumn::Annualized => "annualized basis",
+            SortColumn::NextFunding => "next settlement",
+        }
     }
 
-    let mut truncated = String::with_capacity(MAX_LEN + 1);
-    for (idx, ch) in text.chars().enumerate() {
-        if idx >= MAX_LEN {
-            truncated.push('…');
-            break;
+    fn next(self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|column| *column == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Selection, sort and filter of the Funding Monitor table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableView {
+    pub sort: SortColumn,
+    pub descending: bool,
+    /// Selected row as (venue, symbol), so it follows the instrument when
+    /// rows reorder between ticks.
+    pub selected: Option<(String, String)>,
+    /// Venue/symbol substring; not persisted.
+    #[serde(skip)]
+    pub filter: String,
+    /// Keys go to the filter input while set.
+    #[serde(skip)]
+    pub editing_filter: bool,
+}
+
+impl TableView {
+    pub fn selected_index(&self, rows: &[&MarketSnapshot]) -> Option<usize> {
+        let (venue, symbol) = self.selected.as_ref()?;
+        rows.iter()
+            .position(|row| row.venue == *venue && row.symbol == *symbol)
+    }
+}
+
+/// What the heatmap colors its cells by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMetric {
+    #[default]
+    Funding,
+    Basis,
+}
+
+impl HeatmapMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            HeatmapMetric::Funding => "annualized funding",
+            HeatmapMetric::Basis => "annualized basis",
         }
-        truncated.push(ch);
     }
-    truncated
+}
+
+/// Metric and cursor of the funding heatmap.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatmapView {
+    pub metric: HeatmapMetric,
+    /// Cell under the cursor as (asset, venue).
+    pub cursor: Option<(String, String)>,
+}
+
+impl HeatmapView {
+    /// Cursor as (row, column) in `heatmap`, if that cell is still shown.
+    pub fn cursor_position(&self, heatmap: &Heatmap) -> Option<(usize, usize)> {
+        let (asset, venue) = self.cursor.as_ref()?;
+        Some((
+            heatmap.assets.iter().position(|name| name == asset)?,
+            heatmap.venues.iter().position(|name| name == venue)?,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeatmapCell {
+    pub symbol: String,
+    /// Annual fraction (0.10 = 10%/yr).
+    pub value: f64,
+}
+
+/// Assets by venues; `cells[row][column]` is `None` where the venue lists no
+/// instrument with the metric for that asset.
+#[derive(Debug, Clone, Default)]
+pub struct Heatmap {
+    pub assets: Vec<String>,
+    pub venues: Vec<String>,
+    pub cells: Vec<Vec<Option<HeatmapCell>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -163,8 +1289,74 @@ pub struct AppState {
     pub market_snapshots: Vec<MarketSnapshot>,
     pub metrics_summary: MetricsSummary,
     pub alerts: Vec<AlertStatus>,
-    pub warnings: Vec<String>,
+    /// Fetch errors, reconnects, alert transitions and config changes.
+    pub events: EventLog,
     pub status_line: String,
+    pub stats_window: Option<StatsWindow>,
+    pub opportunities: Vec<CarryOpportunity>,
+    pub forecasts: Vec<FundingForecast>,
+    pub etf_valuations: Vec<EtfValuation>,
+    /// Open instrument picker, if any.
+    pub picker: Option<SymbolPicker>,
+    pub palette: Option<CommandPalette>,
+    /// Outcome of the last user action, shown in the status line.
+    pub notice: Option<String>,
+    pub feed_health: Vec<FeedStats>,
+    /// Index into the configured workspaces.
+    pub workspace: usize,
+    pub previous_workspace: usize,
+    pub table: TableView,
+    pub heatmap: HeatmapView,
+    /// Pane the navigation keys go to; see `QuantumDesk::focused_pane`.
+    pub focus: String,
+    /// Detail view for the selected instrument shown over the body.
+    pub show_detail: bool,
+    /// Key binding overlay.
+    pub show_help: bool,
+    /// Lines the alerts pane is scrolled down by.
+    pub alerts_scroll: u16,
+    /// Lowest severity the log pane shows.
+    pub log_filter: Severity,
+    /// Newer events the log pane is scrolled back past.
+    pub log_scroll: usize,
+    /// When the market panes were frozen; collected data waits in the
+    /// background and history charts end here.
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Instruments with newer data than shown while paused.
+    pub pending_updates: usize,
+}
+
+/// Search-as-you-type instrument picker over the venue directory.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolPicker {
+    pub query: String,
+    pub selected: usize,
+    pub matches: Vec<InstrumentInfo>,
+}
+
+/// Fuzzy command palette over the command registry.
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+    /// Matching command titles, best first.
+    pub matches: Vec<String>,
+    /// Command waiting for its argument, with the prompt to show.
+    pub pending: Option<(String, String)>,
+}
+
+impl CommandPalette {
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+impl SymbolPicker {
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
 }
 
 impl AppState {
@@ -173,8 +1365,34 @@ impl AppState {
             market_snapshots: snapshots,
             metrics_summary: MetricsSummary::default(),
             alerts,
-            warnings: Vec::new(),
+            events: EventLog::default(),
             status_line: "QuantumDesk • Press 'q' to quit".into(),
+            stats_window: None,
+            opportunities: Vec::new(),
+            forecasts: Vec::new(),
+            etf_valuations: Vec::new(),
+            picker: None,
+            palette: None,
+            notice: None,
+            feed_health: Vec::new(),
+            workspace: 0,
+            previous_workspace: 0,
+            table: TableView::default(),
+            heatmap: HeatmapView::default(),
+            focus: FOCUSABLE_PANES[0].to_string(),
+            show_detail: false,
+            show_help: false,
+            alerts_scroll: 0,
+            log_filter: Severity::default(),
+            log_scroll: 0,
+            paused_at: None,
+            pending_updates: 0,
         }
     }
+
+    pub fn forecast(&self, venue: &str, symbol: &str) -> Option<&FundingForecast> {
+        self.forecasts
+            .iter()
+            .find(|forecast| forecast.venue == venue && forecast.symbol == symbol)
+    }
 }
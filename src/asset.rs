@@ -0,0 +1,160 @@
+//! Per-venue symbol mapping for an underlying asset (BTC, ETH, SOL, …).
+//!
+//! Deribit lists BTC and ETH as inverse contracts against a `*_usd` index and
+//! everything else as USDC-margined linear contracts; Bitfinex separates
+//! tickers longer than three characters from the quote with a colon
+//! (`tDOGE:USD`); the other venues follow a single naming pattern.
+
+/// Assets Deribit lists as inverse contracts with their own option chains.
+const DERIBIT_INVERSE: [&str; 2] = ["BTC", "ETH"];
+
+/// Symbols tracked for `asset` on `venue`, spot/index first and perp second.
+pub fn venue_symbols(venue: &str, asset: &str) -> Vec<String> {
+    let asset = asset.to_uppercase();
+    match venue {
+        "Bitfinex" => vec![bitfinex_spot(&asset), bitfinex_perp(&asset)],
+        "Deribit" => vec![deribit_index_symbol(&asset), deribit_perp(&asset)],
+        "Binance" => vec![binance_pair(&asset)],
+        _ => Vec::new(),
+    }
+}
+
+/// Underlying of a venue symbol, recognizing every pattern `venue_symbols`
+/// produces plus Deribit and Binance dated futures.
+pub fn asset_of(venue: &str, symbol: &str) -> Option<String> {
+    let asset = match venue {
+        "Bitfinex" => symbol
+            .strip_suffix("F0:USTF0")
+            .or_else(|| symbol.strip_suffix(":USD"))
+            .or_else(|| symbol.strip_suffix("USD"))?
+            .strip_prefix('t')?,
+        "Deribit" => symbol.split(['-', '_']).next()?,
+        "Binance" => symbol.split('_').next()?.strip_suffix("USDT")?,
+        _ => return None,
+    };
+    (!asset.is_empty()).then(|| asset.to_uppercase())
+}
+
+pub fn bitfinex_spot(asset: &str) -> String {
+    if asset.len() > 3 {
+        format!("t{}:USD", asset)
+    } else {
+        format!("t{}USD", asset)
+    }
+}
+
+pub fn bitfinex_perp(asset: &str) -> String {
+    format!("t{}F0:USTF0", asset)
+}
+
+pub fn deribit_is_inverse(asset: &str) -> bool {
+    DERIBIT_INVERSE.contains(&asset)
+}
+
+/// Display symbol for the Deribit index row, e.g. `BTC-USD` or `SOL-USDC`.
+pub fn deribit_index_symbol(asset: &str) -> String {
+    if deribit_is_inverse(asset) {
+        format!("{}-USD", asset)
+    } else {
+        format!("{}-USDC", asset)
+    }
+}
+
+/// Deribit `index_name` for `get_index_price`, e.g. `btc_usd` or `sol_usdc`.
+pub fn deribit_index_name(asset: &str) -> String {
+    deribit_index_symbol(asset).replace('-', "_").to_lowercase()
+}
+
+pub fn deribit_perp(asset: &str) -> String {
+    if deribit_is_inverse(asset) {
+        format!("{}-PERPETUAL", asset)
+    } else {
+        format!("{}_USDC-PERPETUAL", asset)
+    }
+}
+
+/// `currency` filter for Deribit instrument and option listings.
+pub fn deribit_currency(asset: &str) -> &str {
+    if deribit_is_inverse(asset) {
+        asset
+    } else {
+        "USDC"
+    }
+}
+
+pub fn binance_pair(asset: &str) -> String {
+    format!("{}USDT", asset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSETS: [&str; 4] = ["BTC", "ETH", "SOL", "DOGE"];
+
+    #[test]
+    fn bitfinex_symbols() {
+        assert_eq!(
+            venue_symbols("Bitfinex", "eth"),
+            ["tETHUSD", "tETHF0:USTF0"]
+        );
+        assert_eq!(
+            venue_symbols("Bitfinex", "DOGE"),
+            ["tDOGE:USD", "tDOGEF0:USTF0"]
+        );
+    }
+
+    #[test]
+    fn deribit_symbols() {
+        assert_eq!(
+            venue_symbols("Deribit", "BTC"),
+            ["BTC-USD", "BTC-PERPETUAL"]
+        );
+        assert_eq!(
+            venue_symbols("Deribit", "SOL"),
+            ["SOL-USDC", "SOL_USDC-PERPETUAL"]
+        );
+        assert_eq!(deribit_index_name("SOL"), "sol_usdc");
+        assert_eq!(deribit_currency("ETH"), "ETH");
+        assert_eq!(deribit_currency("SOL"), "USDC");
+    }
+
+    #[test]
+    fn every_venue_symbol_maps_back_to_its_asset() {
+        for venue in ["Bitfinex", "Deribit", "Binance"] {
+            for asset in ASSETS {
+                let symbols = venue_symbols(venue, asset);
+                assert!(!symbols.is_empty(), "{venue} {asset}");
+                for symbol in symbols {
+                    assert_eq!(
+                        asset_of(venue, &symbol).as_deref(),
+                        Some(asset),
+                        "{venue} {symbol}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dated_futures_map_to_their_asset() {
+        assert_eq!(asset_of("Deribit", "ETH-27JUN25").as_deref(), Some("ETH"));
+        assert_eq!(
+            asset_of("Deribit", "SOL_USDC-27JUN25-150-C").as_deref(),
+            Some("SOL")
+        );
+        assert_eq!(
+            asset_of("Binance", "BTCUSDT_250627").as_deref(),
+            Some("BTC")
+        );
+    }
+
+    #[test]
+    fn unknown_symbols_do_not_map() {
+        assert_eq!(asset_of("Bitfinex", "BTCUSD"), None);
+        assert_eq!(asset_of("Bitfinex", "tUSD"), None);
+        assert_eq!(asset_of("Binance", "BTCUSDC"), None);
+        assert_eq!(asset_of("OKX", "BTC-USDT-SWAP"), None);
+        assert!(venue_symbols("OKX", "BTC").is_empty());
+    }
+}
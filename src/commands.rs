@@ -0,0 +1,319 @@
+//! Command palette registry and fuzzy matching.
+
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::ai::AI_ACTIONS;
+use crate::app::{HeatmapMetric, QuantumDesk};
+
+/// What a palette entry does when chosen. `input` is the text typed at the
+/// command's prompt, or empty for commands without one.
+pub type CommandAction = Rc<dyn Fn(&mut QuantumDesk, &str) -> Result<()>>;
+
+#[derive(Clone)]
+pub struct PaletteCommand {
+    pub title: String,
+    /// Asked for before running, e.g. "condition, e.g. funding > 50 bps @Binance".
+    pub prompt: Option<String>,
+    pub action: CommandAction,
+}
+
+impl PaletteCommand {
+    pub fn new(
+        title: impl Into<String>,
+        action: impl Fn(&mut QuantumDesk, &str) -> Result<()> + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            prompt: None,
+            action: Rc::new(action),
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+}
+
+/// Lists the commands a module offers for the current app state.
+pub type CommandProvider = fn(&QuantumDesk) -> Vec<PaletteCommand>;
+
+/// Every module's command provider. Modules add theirs with `register`.
+#[derive(Clone)]
+pub struct CommandRegistry {
+    providers: Vec<CommandProvider>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self {
+            providers: vec![
+                navigation_commands,
+                view_commands,
+                alert_commands,
+                refresh_commands,
+                ai_commands,
+            ],
+        }
+    }
+}
+
+impl CommandRegistry {
+    pub fn register(&mut self, provider: CommandProvider) {
+        self.providers.push(provider);
+    }
+
+    pub fn commands(&self, app: &QuantumDesk) -> Vec<PaletteCommand> {
+        self.providers
+            .iter()
+            .flat_map(|provider| provider(app))
+            .collect()
+    }
+
+    /// Titles matching `query`, best first.
+    pub fn search(&self, app: &QuantumDesk, query: &str, limit: usize) -> Vec<String> {
+        let mut scored: Vec<(i64, String)> = self
+            .commands(app)
+            .into_iter()
+            .filter_map(|command| {
+                fuzzy_score(query, &command.title).map(|score| (score, command.title))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, title)| title)
+            .collect()
+    }
+
+    pub fn find(&self, app: &QuantumDesk, title: &str) -> Option<PaletteCommand> {
+        self.commands(app)
+            .into_iter()
+            .find(|command| command.title == title)
+    }
+}
+
+/// Scores `candidate` against `query` as a case-insensitive subsequence:
+/// consecutive characters and word starts score higher, gaps lower. `None`
+/// when some query character is missing.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().filter(|ch| !ch.is_whitespace()) {
+        let wanted = wanted.to_ascii_lowercase();
+        let found = (position..candidate.len())
+            .find(|idx| candidate[*idx].to_ascii_lowercase() == wanted)?;
+        score += 1;
+        if previous.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position).min(10) as i64;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+fn navigation_commands(app: &QuantumDesk) -> Vec<PaletteCommand> {
+    app.state
+        .market_snapshots
+        .iter()
+        .map(|snapshot| {
+            let venue = snapshot.venue.clone();
+            let symbol = snapshot.symbol.clone();
+            PaletteCommand::new(
+                format!("Go to {} {}", snapshot.venue, snapshot.symbol),
+                move |app, _| {
+                    app.select_instrument(&venue, &symbol);
+                    app.open_detail();
+                    Ok(())
+                },
+            )
+        })
+        .collect()
+}
+
+fn view_commands(app: &QuantumDesk) -> Vec<PaletteCommand> {
+    let mut commands = vec![
+        PaletteCommand::new(
+            if app.workspace().name == "health" {
+                "Hide feed health pane"
+            } else {
+                "Show feed health pane"
+            },
+            |app, _| {
+                app.toggle_health_pane();
+                Ok(())
+            },
+        ),
+        PaletteCommand::new("Cycle funding stats window", |app, _| {
+            app.cycle_stats_window();
+            Ok(())
+        }),
+        PaletteCommand::new(
+            if app.is_compact() {
+                "Layout: full"
+            } else {
+                "Layout: compact"
+            },
+            |app, _| {
+                app.set_compact(!app.is_compact());
+                Ok(())
+            },
+        ),
+        PaletteCommand::new("Cycle table sort column", |app, _| {
+            app.cycle_table_sort();
+            Ok(())
+        }),
+        PaletteCommand::new(
+            match app.state.heatmap.metric {
+                HeatmapMetric::Funding => "Heatmap: color by basis",
+                HeatmapMetric::Basis => "Heatmap: color by funding",
+            },
+            |app, _| {
+                app.toggle_heatmap_metric();
+                Ok(())
+            },
+        ),
+        PaletteCommand::new(
+            format!("Log: show {}+", app.state.log_filter.next()),
+            |app, _| {
+                app.cycle_log_filter();
+                Ok(())
+            },
+        ),
+        PaletteCommand::new("Reload config", |app, _| {
+            app.reload_config();
+            Ok(())
+        }),
+        PaletteCommand::new("Add instrument…", |app, _| {
+            app.open_symbol_picker();
+            Ok(())
+        }),
+        PaletteCommand::new("Set refresh interval…", |app, input| {
+            app.set_refresh_interval(input.trim().trim_end_matches("ms").parse()?);
+            Ok(())
+        })
+        .with_prompt(format!(
+            "refresh interval in ms (now {})",
+            app.refresh_interval_ms()
+        )),
+    ];
+    commands.extend(
+        app.workspaces()
+            .iter()
+            .enumerate()
+            .map(|(index, workspace)| {
+                PaletteCommand::new(
+                    format!("Workspace: {} ({})", workspace.name, index + 1),
+                    move |app, _| {
+                        app.switch_workspace(index);
+                        Ok(())
+                    },
+                )
+            }),
+    );
+    commands
+}
+
+fn alert_commands(app: &QuantumDesk) -> Vec<PaletteCommand> {
+    let mut commands = vec![
+        PaletteCommand::new("Alert: create…", |app, input| app.create_alert(input))
+            .with_prompt("condition [@venue], e.g. funding > 50 bps @Binance"),
+    ];
+    for alert in &app.state.alerts {
+        let name = alert.name.clone();
+        let verb = if alert.muted { "unmute" } else { "mute" };
+        commands.push(PaletteCommand::new(
+            format!("Alert: {} {}", verb, alert.name),
+            move |app, _| {
+                app.toggle_alert_mute(&name);
+                Ok(())
+            },
+        ));
+        if alert.needs_attention() {
+            let name = alert.name.clone();
+            commands.push(PaletteCommand::new(
+                format!("Alert: acknowledge {}", alert.name),
+                move |app, _| {
+                    app.acknowledge_alert(&name);
+                    Ok(())
+                },
+            ));
+        }
+    }
+    commands
+}
+
+fn refresh_commands(app: &QuantumDesk) -> Vec<PaletteCommand> {
+    let mut commands = vec![
+        PaletteCommand::new(
+            if app.state.paused_at.is_some() {
+                "Resume display"
+            } else {
+                "Pause display"
+            },
+            |app, _| {
+                app.toggle_pause();
+                Ok(())
+            },
+        ),
+        PaletteCommand::new("Refresh: all venues", |app, _| {
+            app.force_refresh(None);
+            Ok(())
+        }),
+    ];
+    commands.extend(app.refreshable_venues().into_iter().map(|venue| {
+        PaletteCommand::new(format!("Refresh: {}", venue), move |app, _| {
+            app.force_refresh(Some(&venue));
+            Ok(())
+        })
+    }));
+    commands
+}
+
+fn ai_commands(_app: &QuantumDesk) -> Vec<PaletteCommand> {
+    AI_ACTIONS
+        .iter()
+        .map(|(id, title)| {
+            PaletteCommand::new(format!("AI: {}", title), move |app, _| {
+                app.run_ai_action(id)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn missing_characters_do_not_match() {
+        assert_eq!(fuzzy_score("xyz", "Go to Binance BTCUSDT"), None);
+        assert_eq!(fuzzy_score("tb", "bt"), None);
+    }
+
+    #[test]
+    fn matching_ignores_case_and_spaces() {
+        assert!(fuzzy_score("GO BIN", "go to binance").is_some());
+        assert!(fuzzy_score("", "anything").is_some());
+    }
+
+    #[test]
+    fn consecutive_word_start_matches_rank_first() {
+        let prefix = fuzzy_score("pause", "Pause updates").unwrap();
+        let scattered = fuzzy_score("pause", "Open panes: a user settings").unwrap();
+        assert!(prefix > scattered);
+        let word_start = fuzzy_score("ref", "Refresh: all venues").unwrap();
+        let inner = fuzzy_score("ref", "Show preferences").unwrap();
+        assert!(word_start > inner);
+    }
+}
@@ -1,11 +1,49 @@
-use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::etf::{EtfConfig, EtfQuoteSourceConfig};
+use crate::forecast::FundingModel;
+use crate::keymap::KeymapConfig;
+use crate::layout::{self, WorkspaceConfig};
+use crate::metrics::StatsWindow;
+use crate::ratelimit::RateLimitConfig;
+use crate::scanner::FeeTier;
+use crate::theme::ThemeConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub update_interval_ms: u64,
     pub cache_ttl_secs: u64,
+    /// Underlyings to track; each fans out to its symbol on every venue.
+    /// Accepts `asset = "ETH"` as shorthand for a single entry.
+    #[serde(alias = "asset", deserialize_with = "one_or_many")]
+    pub assets: Vec<String>,
     pub venues: Vec<VenueConfig>,
     pub compact_mode: bool,
+    /// Mouse clicks and scrolling in the UI.
+    pub mouse: bool,
+    pub alerts: Vec<AlertConfig>,
+    /// Rolling window whose funding statistics are shown as table columns.
+    pub stats_window: Option<StatsWindow>,
+    /// Holding period the opportunity scanner ranks carry trades over.
+    pub carry_horizon_hours: u64,
+    pub fee_tier: FeeTier,
+    /// Where persisted history and caches live.
+    pub data_dir: PathBuf,
+    pub realized_vol: RealizedVolConfig,
+    /// Spot ETF premium/discount tracking; inactive until the holdings file exists.
+    pub etf: Option<EtfConfig>,
+    /// Named pane layouts, switched with the number keys in this order.
+    pub workspaces: Vec<WorkspaceConfig>,
+    pub theme: ThemeConfig,
+    pub keymap: KeymapConfig,
+    /// File the config was read from; `None` when running on defaults.
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
 }
 
 impl Default for AppConfig {
@@ -13,17 +51,167 @@ impl Default for AppConfig {
         Self {
             update_interval_ms: 1_000,
             cache_ttl_secs: 60,
+            assets: vec!["BTC".into()],
             venues: vec![
                 VenueConfig {
                     name: "Bitfinex".into(),
-                    symbols: vec!["tBTCUSD".into(), "tBTCF0:USTF0".into()],
+                    symbols: Vec::new(),
+                    dated_futures: false,
+                    options: false,
+                    maker_fee_bps: 10.0,
+                    taker_fee_bps: 20.0,
+                    funding_interval_hours: 8,
+                    funding_model: FundingModel {
+                        cap_bps: 75.0,
+                        ..FundingModel::default()
+                    },
+                    // Public REST: 90 requests/min per endpoint family.
+                    rate_limit: RateLimitConfig {
+                        capacity: 90.0,
+                        refill_per_sec: 1.5,
+                        ..RateLimitConfig::default()
+                    },
                 },
                 VenueConfig {
                     name: "Deribit".into(),
-                    symbols: vec!["BTC-USD".into(), "BTC-PERPETUAL".into()],
+                    symbols: Vec::new(),
+                    dated_futures: true,
+                    options: true,
+                    maker_fee_bps: 0.0,
+                    taker_fee_bps: 5.0,
+                    funding_interval_hours: 8,
+                    funding_model: FundingModel {
+                        dampener_bps: 5.0,
+                        cap_bps: 50.0,
+                        ..FundingModel::default()
+                    },
+                    // Non-matching-engine credits: 20/s sustained, burst 100.
+                    rate_limit: RateLimitConfig {
+                        capacity: 100.0,
+                        refill_per_sec: 20.0,
+                        ..RateLimitConfig::default()
+                    },
+                },
+                VenueConfig {
+                    name: "Binance".into(),
+                    symbols: Vec::new(),
+                    dated_futures: true,
+                    options: false,
+                    maker_fee_bps: 2.0,
+                    taker_fee_bps: 5.0,
+                    funding_interval_hours: 8,
+                    funding_model: FundingModel {
+                        interest_rate_bps: 1.0,
+                        clamp_bps: 5.0,
+                        cap_bps: 300.0,
+                        ..FundingModel::default()
+                    },
+                    // 2400 request weight per minute per IP. Symbol-less
+                    // ticker and premium index requests cost far more.
+                    rate_limit: RateLimitConfig {
+                        capacity: 2_400.0,
+                        refill_per_sec: 40.0,
+                        weights: [
+                            ("exchangeInfo", 1.0),
+                            ("openInterest", 1.0),
+                            ("premiumIndex", 10.0),
+                            ("premiumIndex?symbol=", 1.0),
+                            ("ticker/24hr", 40.0),
+                            ("ticker/24hr?symbol=", 1.0),
+                        ]
+                        .into_iter()
+                        .map(|(endpoint, weight)| (endpoint.to_string(), weight))
+                        .collect(),
+                    },
                 },
             ],
             compact_mode: false,
+            mouse: true,
+            alerts: vec![
+                AlertConfig {
+                    name: "Bitfinex Funding".into(),
+                    asset: None,
+                    venue: Some("Bitfinex".into()),
+                    symbol: None,
+                    condition: "funding > 75 bps".into(),
+                },
+                AlertConfig {
+                    name: "Deribit Funding".into(),
+                    asset: None,
+                    venue: Some("Deribit".into()),
+                    symbol: None,
+                    condition: "funding < -25 bps".into(),
+                },
+                AlertConfig {
+                    name: "IBIT Premium".into(),
+                    asset: None,
+                    venue: Some("ETF".into()),
+                    symbol: Some("IBIT".into()),
+                    condition: "premium > 1.5%".into(),
+                },
+            ],
+            stats_window: None,
+            carry_horizon_hours: 24,
+            fee_tier: FeeTier::Taker,
+            data_dir: default_data_dir(),
+            realized_vol: RealizedVolConfig::default(),
+            etf: Some(default_etf_config(&default_data_dir())),
+            workspaces: layout::default_workspaces(),
+            theme: ThemeConfig::default(),
+            keymap: KeymapConfig::default(),
+            source: None,
+        }
+    }
+}
+
+fn default_etf_config(data_dir: &Path) -> EtfConfig {
+    EtfConfig {
+        tickers: vec!["IBIT".into(), "FBTC".into()],
+        holdings_path: data_dir.join("etf_holdings.csv"),
+        quotes: EtfQuoteSourceConfig::Csv {
+            path: data_dir.join("etf_quotes.csv"),
+        },
+        index_venue: "Deribit".into(),
+        index_symbol: "BTC-USD".into(),
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(asset) => vec![asset.to_uppercase()],
+        OneOrMany::Many(assets) => assets.iter().map(|asset| asset.to_uppercase()).collect(),
+    })
+}
+
+fn default_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("quantumdesk")
+}
+
+/// Bar size used to resample snapshot prices, and the trailing windows
+/// realized volatility and correlation are computed over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealizedVolConfig {
+    pub bar_minutes: u64,
+    pub windows_hours: Vec<u64>,
+}
+
+impl Default for RealizedVolConfig {
+    fn default() -> Self {
+        Self {
+            bar_minutes: 60,
+            windows_hours: vec![24, 24 * 7],
         }
     }
 }
@@ -31,23 +219,134 @@ impl Default for AppConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VenueConfig {
     pub name: String,
+    /// Extra venue symbols tracked on top of the configured assets.
+    #[serde(default)]
     pub symbols: Vec<String>,
+    /// Discover and track every listed dated future alongside `symbols`.
+    #[serde(default)]
+    pub dated_futures: bool,
+    /// Ingest the venue option chain for volatility surface metrics.
+    #[serde(default)]
+    pub options: bool,
+    #[serde(default)]
+    pub maker_fee_bps: f64,
+    #[serde(default)]
+    pub taker_fee_bps: f64,
+    #[serde(default = "default_funding_interval_hours")]
+    pub funding_interval_hours: u64,
+    #[serde(default)]
+    pub funding_model: FundingModel,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+}
+
+fn default_funding_interval_hours() -> u64 {
+    8
+}
+
+/// A named alert rule. `condition` is an expression such as
+/// `funding > 75 bps` or `funding_z_7d >= 2`; see `alerts::AlertCondition`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    pub name: String,
+    #[serde(default)]
+    pub asset: Option<String>,
+    #[serde(default)]
+    pub venue: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    pub condition: String,
 }
 
 impl AppConfig {
+    pub fn history_path(&self) -> PathBuf {
+        self.data_dir.join("history.json")
+    }
+
+    pub fn instruments_path(&self) -> PathBuf {
+        self.data_dir.join("instruments.json")
+    }
+
+    pub fn watchlist_path(&self) -> PathBuf {
+        self.data_dir.join("watchlist.json")
+    }
+
+    pub fn snapshot_cache_path(&self) -> PathBuf {
+        self.data_dir.join("snapshot_cache.json")
+    }
+
+    pub fn ui_state_path(&self) -> PathBuf {
+        self.data_dir.join("ui_state.json")
+    }
+
+    /// Adds `symbol` to the named venue's extra symbols; returns whether the
+    /// venue exists and the symbol was not already tracked.
+    pub fn watch(&mut self, venue: &str, symbol: &str) -> bool {
+        let Some(config) = self.venues.iter_mut().find(|config| config.name == venue) else {
+            return false;
+        };
+        if config.symbols.iter().any(|known| known == symbol) {
+            return false;
+        }
+        config.symbols.push(symbol.to_string());
+        true
+    }
+
+    /// Default location of the TOML config file.
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("quantumdesk")
+            .join("config.toml")
+    }
+
+    /// Reads a TOML config; keys left out keep their defaults. A missing file
+    /// at the default location is not an error.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => (Self::default_path(), false),
+        };
+        if !required && !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        let config: Self =
+            toml::from_str(&raw).with_context(|| format!("invalid config {}", path.display()))?;
+        Ok(Self {
+            source: Some(path),
+            ..config
+        })
+    }
+
     pub fn with_overrides(
+        mut self,
         update_interval_ms: Option<u64>,
         cache_ttl_secs: Option<u64>,
         compact_mode: bool,
+        stats_window: Option<StatsWindow>,
+        carry_horizon_hours: Option<u64>,
+        assets: Vec<String>,
     ) -> Self {
-        let mut base = Self::default();
         if let Some(ms) = update_interval_ms {
-            base.update_interval_ms = ms.max(100);
+            self.update_interval_ms = ms.max(100);
         }
         if let Some(ttl) = cache_ttl_secs {
-            base.cache_ttl_secs = ttl.max(5);
+            self.cache_ttl_secs = ttl.max(5);
+        }
+        if compact_mode {
+            self.compact_mode = true;
+        }
+        if stats_window.is_some() {
+            self.stats_window = stats_window;
+        }
+        if let Some(hours) = carry_horizon_hours {
+            self.carry_horizon_hours = hours.max(1);
+        }
+        if !assets.is_empty() {
+            self.assets = assets.iter().map(|asset| asset.to_uppercase()).collect();
         }
-        base.compact_mode = compact_mode;
-        base
+        self
     }
 }
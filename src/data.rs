@@ -1,27 +1,75 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Instant;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result, anyhow};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
-use reqwest::blocking::Client;
-use serde::Deserialize;
+use reqwest::blocking::{Client, Response};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::config::AppConfig;
+use crate::asset;
+use crate::config::{AppConfig, VenueConfig};
+use crate::discovery::{InstrumentDirectory, InstrumentInfo};
+use crate::health::{CircuitBreaker, FeedHealth, FeedSource, FeedStats};
+use crate::persist;
+use crate::ratelimit::{BudgetExhausted, RateLimiter};
 
-#[derive(Debug, Clone)]
+/// Catalog listings change slowly; re-query them hourly at most.
+const CATALOG_TTL_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstrumentKind {
+    Spot,
+    Index,
+    Perp,
+    Future,
+    /// Exchange-traded fund share, valued against the BTC it holds.
+    Etf,
+    /// Listed option, priced at its mark premium in quote currency.
+    Option,
+}
+
+impl InstrumentKind {
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "Perp" => Self::Perp,
+            "Future" => Self::Future,
+            "Index" => Self::Index,
+            "ETF" => Self::Etf,
+            "Option" => Self::Option,
+            _ => Self::Spot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketSnapshot {
     pub venue: String,
     pub instrument_label: String,
     pub symbol: String,
+    /// Underlying the instrument tracks, e.g. `BTC`.
+    pub asset: String,
+    pub kind: InstrumentKind,
     pub spot_price: f64,
+    /// Derivative leg: perp price for perps, contract price for dated futures.
     pub perp_price: Option<f64>,
+    pub index_price: Option<f64>,
+    pub mark_price: Option<f64>,
     pub funding_rate: f64,
     pub predicted_funding_rate: Option<f64>,
     pub next_funding_time: Option<DateTime<Utc>>,
+    pub expiry: Option<DateTime<Utc>>,
+    /// Open interest in quote-currency (USD) notional.
+    pub open_interest: Option<f64>,
+    /// Trailing 24h traded volume in quote-currency (USD) notional.
+    pub volume_24h: Option<f64>,
     pub last_updated: DateTime<Utc>,
 }
 
 impl MarketSnapshot {
+    #[allow(clippy::too_many_arguments)]
     pub fn placeholder(
         venue: impl Into<String>,
         instrument_label: impl Into<String>,
@@ -32,41 +80,122 @@ impl MarketSnapshot {
         predicted_funding_rate: Option<f64>,
         next_funding_time: Option<DateTime<Utc>>,
     ) -> Self {
+        let venue = venue.into();
+        let instrument_label = instrument_label.into();
+        let symbol = symbol.into();
         Self {
-            venue: venue.into(),
-            instrument_label: instrument_label.into(),
-            symbol: symbol.into(),
+            asset: asset::asset_of(&venue, &symbol).unwrap_or_default(),
+            venue,
+            kind: InstrumentKind::from_label(&instrument_label),
+            instrument_label,
+            symbol,
             spot_price,
             perp_price,
+            index_price: None,
+            mark_price: perp_price,
             funding_rate,
             predicted_funding_rate,
             next_funding_time,
+            expiry: None,
+            open_interest: None,
+            volume_24h: None,
             last_updated: Utc::now(),
         }
     }
+
+    pub fn is_perp(&self) -> bool {
+        self.kind == InstrumentKind::Perp
+    }
+
+    pub fn is_dated_future(&self) -> bool {
+        self.kind == InstrumentKind::Future
+    }
+
+    /// Reference price the perp is measured against: the venue index when
+    /// published, otherwise the spot leg recorded on the snapshot.
+    pub fn reference_price(&self) -> f64 {
+        self.index_price.unwrap_or(self.spot_price)
+    }
 }
 
 pub struct CollectionOutcome {
     pub snapshots: Vec<MarketSnapshot>,
+    /// One chain per venue and asset with options enabled.
+    pub options: Vec<OptionChain>,
     pub warnings: Vec<String>,
 }
 
+/// One listed option from a venue book summary.
+#[derive(Debug, Clone)]
+pub struct OptionQuote {
+    pub symbol: String,
+    pub expiry: DateTime<Utc>,
+    pub strike: f64,
+    pub is_call: bool,
+    /// Mark implied volatility as a fraction (0.55 = 55 vol).
+    pub mark_iv: f64,
+    /// Forward/underlying price the venue marks this expiry against.
+    pub underlying_price: f64,
+    pub open_interest: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OptionChain {
+    pub venue: String,
+    pub asset: String,
+    pub quotes: Vec<OptionQuote>,
+    /// Venue volatility index (Deribit DVOL) as a fraction.
+    pub volatility_index: Option<f64>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A dated futures contract discovered from venue instrument metadata.
+#[derive(Debug, Clone)]
+pub struct FutureContract {
+    pub venue: String,
+    pub asset: String,
+    pub symbol: String,
+    pub expiry: DateTime<Utc>,
+}
+
 pub struct DataHub {
     http: Client,
     cache: HashMap<String, CachedSnapshot>,
-    status_label: String,
+    catalogs: HashMap<String, CachedCatalog>,
+    option_chains: HashMap<String, OptionChain>,
+    directory: InstrumentDirectory,
+    /// Behind a `RefCell` so the `&self` fetchers can spend budget.
+    limiter: RefCell<RateLimiter>,
+    health: FeedHealth,
+    /// Venues the next collection re-fetches regardless of the cache TTL.
+    forced: HashSet<String>,
+    /// Optional fields a fetch had to leave empty; reported with the
+    /// collection's warnings.
+    partial: RefCell<Vec<String>>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct CachedSnapshot {
     snapshot: MarketSnapshot,
     fetched_at: DateTime<Utc>,
 }
 
+struct CachedCatalog {
+    contracts: Vec<FutureContract>,
+    fetched_at: DateTime<Utc>,
+}
+
 enum SnapshotOutcome {
     Fresh(MarketSnapshot),
     Stale(MarketSnapshot, String),
 }
 
+impl Default for DataHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DataHub {
     pub fn new() -> Self {
         let http = Client::builder()
@@ -77,47 +206,295 @@ impl DataHub {
         Self {
             http,
             cache: HashMap::new(),
-            status_label: "Initializing feeds".into(),
+            catalogs: HashMap::new(),
+            option_chains: HashMap::new(),
+            directory: InstrumentDirectory::default(),
+            limiter: RefCell::new(RateLimiter::default()),
+            health: FeedHealth::default(),
+            forced: HashSet::new(),
+            partial: RefCell::new(Vec::new()),
         }
     }
 
-    pub fn status(&self) -> &str {
-        &self.status_label
+    /// Circuit breaker state per venue that has been queried.
+    pub fn status(&self) -> Vec<(String, CircuitBreaker)> {
+        self.health.venues()
+    }
+
+    /// Makes the next `collect` re-fetch `venue` even where its cached data
+    /// is younger than the TTL. Backoff and rate limits still apply; returns
+    /// why requests will be held back, if they will.
+    pub fn force_refresh(&mut self, venue: &str) -> Option<String> {
+        self.forced.insert(venue.to_string());
+        let now = Utc::now();
+        self.health.held_back(venue, now).or_else(|| {
+            self.limiter
+                .borrow()
+                .blocked_until(venue)
+                .map(|until| format!("rate limited until {}", until.format("%H:%M:%S")))
+        })
     }
 
     pub fn collect(&mut self, config: &AppConfig) -> CollectionOutcome {
-        let ttl = ChronoDuration::seconds(config.cache_ttl_secs as i64);
+        {
+            let mut limiter = self.limiter.borrow_mut();
+            limiter.configure(&config.venues);
+            limiter.begin_tick();
+        }
+        let forced = std::mem::take(&mut self.forced);
+        let cache_ttl = ChronoDuration::seconds(config.cache_ttl_secs as i64);
+        let venue_ttl = |venue: &str| {
+            if forced.contains(venue) {
+                ChronoDuration::zero()
+            } else {
+                cache_ttl
+            }
+        };
         let mut snapshots = Vec::new();
+        let mut options = Vec::new();
         let mut warnings = Vec::new();
 
-        for venue in &config.venues {
-            for symbol in &venue.symbols {
-                match self.load_snapshot(&venue.name, symbol, ttl) {
-                    Ok(SnapshotOutcome::Fresh(snapshot)) => snapshots.push(snapshot),
-                    Ok(SnapshotOutcome::Stale(snapshot, warning)) => {
-                        snapshots.push(snapshot);
-                        warnings.push(warning);
+        for asset in &config.assets {
+            for venue in &config.venues {
+                let ttl = venue_ttl(&venue.name);
+                if venue.options {
+                    match self.load_option_chain(&venue.name, asset, ttl) {
+                        Ok(chain) => options.push(chain),
+                        Err(err) => warnings.push(format!(
+                            "{} {} options fetch failed ({:#})",
+                            venue.name, asset, err
+                        )),
+                    }
+                }
+
+                let mut symbols = asset::venue_symbols(&venue.name, asset);
+                if venue.dated_futures {
+                    match self.dated_futures(&venue.name, asset) {
+                        Ok(contracts) => {
+                            symbols.extend(contracts.into_iter().map(|contract| contract.symbol))
+                        }
+                        Err(err) => warnings.push(format!(
+                            "{} {} futures listing failed ({:#})",
+                            venue.name, asset, err
+                        )),
                     }
-                    Err(err) => warnings.push(err.to_string()),
+                }
+                for symbol in &symbols {
+                    self.push_snapshot(
+                        &venue.name,
+                        asset,
+                        symbol,
+                        ttl,
+                        &mut snapshots,
+                        &mut warnings,
+                    );
                 }
             }
         }
 
-        self.status_label = if warnings.is_empty() {
-            "Live feeds stable".into()
-        } else {
-            "Live feeds (degraded)".into()
-        };
+        // Explicitly listed symbols on top of the asset fan-out.
+        for venue in &config.venues {
+            let ttl = venue_ttl(&venue.name);
+            for symbol in &venue.symbols {
+                if snapshots
+                    .iter()
+                    .any(|s| s.venue == venue.name && s.symbol == *symbol)
+                {
+                    continue;
+                }
+                match asset::asset_of(&venue.name, symbol) {
+                    Some(asset) => self.push_snapshot(
+                        &venue.name,
+                        &asset,
+                        symbol,
+                        ttl,
+                        &mut snapshots,
+                        &mut warnings,
+                    ),
+                    None => warnings.push(format!(
+                        "{} {}: cannot map symbol to an asset",
+                        venue.name, symbol
+                    )),
+                }
+            }
+        }
 
+        warnings.append(&mut self.partial.borrow_mut());
         CollectionOutcome {
             snapshots,
+            options,
             warnings,
         }
     }
 
+    /// Request statistics per venue/symbol feed.
+    pub fn feed_health(&self) -> Vec<FeedStats> {
+        self.health.feeds()
+    }
+
+    /// Extra wait before the next tick so no venue overruns its budget.
+    pub fn tick_delay(&self) -> ChronoDuration {
+        self.limiter.borrow().tick_delay()
+    }
+
+    /// Remaining request budget per venue, as a fraction of capacity.
+    pub fn rate_budget(&self) -> Vec<(String, f64)> {
+        self.limiter.borrow().remaining()
+    }
+
+    /// Restores snapshots cached by a previous run, keeping their original
+    /// fetch times so they age out and show as stale like any other entry.
+    pub fn load_cache(&mut self, path: &Path) -> Result<()> {
+        let entries: Vec<CachedSnapshot> =
+            persist::load_json(path, "snapshot cache")?.unwrap_or_default();
+        for entry in entries {
+            let key = cache_key(&entry.snapshot.venue, &entry.snapshot.symbol);
+            self.health.record_served(
+                &entry.snapshot.venue,
+                &entry.snapshot.symbol,
+                FeedSource::StaleCache,
+                entry.fetched_at,
+            );
+            self.cache.insert(key, entry);
+        }
+        Ok(())
+    }
+
+    pub fn save_cache(&self, path: &Path) -> Result<()> {
+        let entries: Vec<&CachedSnapshot> = self.cache.values().collect();
+        persist::save_json(path, &entries, "snapshot cache")
+    }
+
+    /// Cached snapshots for the configured venues and assets (or a venue's
+    /// extra symbols), in venue order, for display before the first fetch
+    /// completes.
+    pub fn cached_snapshots(&self, config: &AppConfig) -> Vec<MarketSnapshot> {
+        let venues = &config.venues;
+        let mut snapshots: Vec<&CachedSnapshot> = self
+            .cache
+            .values()
+            .filter(|entry| {
+                let snapshot = &entry.snapshot;
+                venues.iter().any(|venue| {
+                    venue.name == snapshot.venue
+                        && (config.assets.contains(&snapshot.asset)
+                            || venue.symbols.contains(&snapshot.symbol))
+                })
+            })
+            .collect();
+        snapshots.sort_by_key(|entry| {
+            (
+                venues
+                    .iter()
+                    .position(|venue| venue.name == entry.snapshot.venue),
+                entry.snapshot.symbol.clone(),
+            )
+        });
+        snapshots
+            .into_iter()
+            .map(|entry| entry.snapshot.clone())
+            .collect()
+    }
+
+    pub fn directory(&self) -> &InstrumentDirectory {
+        &self.directory
+    }
+
+    pub fn load_directory(&mut self, path: &Path) -> Result<()> {
+        self.directory = InstrumentDirectory::load(path)?;
+        Ok(())
+    }
+
+    /// Re-lists every configured venue and writes the directory to `path`.
+    /// A venue whose listing fails keeps its previous entries.
+    pub fn refresh_directory(&mut self, venues: &[VenueConfig], path: &Path) -> Vec<String> {
+        self.limiter.borrow_mut().configure(venues);
+        let mut warnings = Vec::new();
+        for venue in venues {
+            let listing = match venue.name.as_str() {
+                "Bitfinex" => self.fetch_bitfinex_listing(),
+                "Deribit" => self.fetch_deribit_listing(),
+                "Binance" => self.fetch_binance_listing(),
+                other => Err(anyhow!("{} has no instrument listing", other)),
+            };
+            match listing {
+                Ok(instruments) => self.directory.replace_venue(&venue.name, instruments),
+                Err(err) => warnings.push(format!("{} listing failed ({:#})", venue.name, err)),
+            }
+        }
+        // Retry next time if nothing could be listed at all.
+        if warnings.len() == venues.len() {
+            return warnings;
+        }
+        self.directory.fetched_at = Some(Utc::now());
+        if let Err(err) = self.directory.save(path) {
+            warnings.push(format!("{:#}", err));
+        }
+        warnings
+    }
+
+    fn push_snapshot(
+        &mut self,
+        venue: &str,
+        asset: &str,
+        symbol: &str,
+        ttl: ChronoDuration,
+        snapshots: &mut Vec<MarketSnapshot>,
+        warnings: &mut Vec<String>,
+    ) {
+        match self.load_snapshot(venue, asset, symbol, ttl) {
+            Ok(SnapshotOutcome::Fresh(snapshot)) => snapshots.push(snapshot),
+            Ok(SnapshotOutcome::Stale(snapshot, warning)) => {
+                snapshots.push(snapshot);
+                warnings.push(warning);
+            }
+            Err(err) => warnings.push(format!("{:#}", err)),
+        }
+    }
+
+    /// Latest option chain for a venue and asset, re-fetched once older than `ttl` and
+    /// served from the previous fetch when the venue call fails.
+    fn load_option_chain(
+        &mut self,
+        venue: &str,
+        asset: &str,
+        ttl: ChronoDuration,
+    ) -> Result<OptionChain> {
+        let key = cache_key(venue, asset);
+        let subject = format!("{} options", asset);
+        let now = Utc::now();
+        if let Some(chain) = self.option_chains.get(&key)
+            && now - chain.fetched_at < ttl
+        {
+            let chain = chain.clone();
+            self.health
+                .record_served(venue, &subject, FeedSource::Rest, chain.fetched_at);
+            return Ok(chain);
+        }
+
+        let fetched = self.guarded(venue, &subject, |hub| match venue {
+            "Deribit" => hub.fetch_deribit_options(asset),
+            _ => Err(anyhow!("{} does not list options", venue)),
+        });
+        let (chain, source) = match fetched {
+            Ok(chain) => {
+                self.option_chains.insert(key, chain.clone());
+                (chain, FeedSource::Rest)
+            }
+            Err(err) => (
+                self.option_chains.get(&key).cloned().ok_or(err)?,
+                FeedSource::StaleCache,
+            ),
+        };
+        self.health
+            .record_served(venue, &subject, source, chain.fetched_at);
+        Ok(chain)
+    }
+
     fn load_snapshot(
         &mut self,
         venue: &str,
+        asset: &str,
         symbol: &str,
         ttl: ChronoDuration,
     ) -> Result<SnapshotOutcome> {
@@ -127,13 +504,20 @@ impl DataHub {
         if let Some(entry) = self.cache.get(&key) {
             let age = now - entry.fetched_at;
             if age < ttl {
-                return Ok(SnapshotOutcome::Fresh(entry.snapshot.clone()));
+                let snapshot = entry.snapshot.clone();
+                self.health
+                    .record_served(venue, symbol, FeedSource::Rest, entry.fetched_at);
+                return Ok(SnapshotOutcome::Fresh(snapshot));
             }
         }
 
-        match self.fetch_snapshot(venue, symbol) {
+        match self.guarded(venue, symbol, |hub| {
+            hub.fetch_snapshot(venue, asset, symbol)
+        }) {
             Ok(mut snapshot) => {
                 snapshot.last_updated = now;
+                self.health
+                    .record_served(venue, symbol, FeedSource::Rest, now);
                 self.cache.insert(
                     key,
                     CachedSnapshot {
@@ -145,16 +529,22 @@ impl DataHub {
             }
             Err(fetch_err) => {
                 if let Some(entry) = self.cache.get(&key) {
+                    self.health.record_served(
+                        venue,
+                        symbol,
+                        FeedSource::StaleCache,
+                        entry.fetched_at,
+                    );
                     return Ok(SnapshotOutcome::Stale(
                         entry.snapshot.clone(),
                         format!(
-                            "{} {} fetch failed ({}); showing cached data",
+                            "{} {} fetch failed ({:#}); showing cached data",
                             venue, symbol, fetch_err
                         ),
                     ));
                 }
                 Err(anyhow!(
-                    "{} {} fetch failed and no cache available ({})",
+                    "{} {} fetch failed and no cache available ({:#})",
                     venue,
                     symbol,
                     fetch_err
@@ -163,12 +553,137 @@ impl DataHub {
         }
     }
 
-    fn fetch_snapshot(&self, venue: &str, symbol: &str) -> Result<MarketSnapshot> {
-        match (venue, symbol) {
-            ("Bitfinex", "tBTCUSD") => self.fetch_bitfinex_spot(symbol),
-            ("Bitfinex", "tBTCF0:USTF0") => self.fetch_bitfinex_perp(symbol),
-            ("Deribit", "BTC-USD") => self.fetch_deribit_index(symbol),
-            ("Deribit", "BTC-PERPETUAL") => self.fetch_deribit_perp(symbol),
+    /// Active dated futures on `asset` for a venue, served from the catalog
+    /// cache and falling back to the last good listing when the metadata call
+    /// fails.
+    pub fn dated_futures(&mut self, venue: &str, asset: &str) -> Result<Vec<FutureContract>> {
+        let key = cache_key(venue, asset);
+        let subject = format!("{} futures", asset);
+        let now = Utc::now();
+        if let Some(entry) = self.catalogs.get(&key)
+            && now - entry.fetched_at < ChronoDuration::minutes(CATALOG_TTL_MINUTES)
+        {
+            let contracts = entry.contracts.clone();
+            self.health
+                .record_served(venue, &subject, FeedSource::Rest, entry.fetched_at);
+            return Ok(contracts);
+        }
+
+        let fetched = self.guarded(venue, &subject, |hub| match venue {
+            "Deribit" => hub.fetch_deribit_futures(asset),
+            "Binance" => hub.fetch_binance_futures(asset),
+            _ => Err(anyhow!("{} does not list dated futures", venue)),
+        });
+
+        match fetched {
+            Ok(mut contracts) => {
+                contracts.retain(|contract| contract.expiry > now);
+                contracts.sort_by_key(|contract| contract.expiry);
+                self.catalogs.insert(
+                    key,
+                    CachedCatalog {
+                        contracts: contracts.clone(),
+                        fetched_at: now,
+                    },
+                );
+                self.health
+                    .record_served(venue, &subject, FeedSource::Rest, now);
+                Ok(contracts)
+            }
+            Err(err) => {
+                let entry = self.catalogs.get(&key).ok_or(err)?;
+                let contracts = entry.contracts.clone();
+                let fetched_at = entry.fetched_at;
+                self.health
+                    .record_served(venue, &subject, FeedSource::StaleCache, fetched_at);
+                Ok(contracts)
+            }
+        }
+    }
+
+    /// Runs `fetch` behind the venue's circuit breaker and the feed's retry
+    /// backoff, timing it for the health pane. Requests held back locally do
+    /// not count against the venue.
+    fn guarded<T>(
+        &mut self,
+        venue: &str,
+        subject: &str,
+        fetch: impl FnOnce(&Self) -> Result<T>,
+    ) -> Result<T> {
+        self.health
+            .check(venue, subject, Utc::now())
+            .map_err(|held| anyhow!(held))?;
+        let started = Instant::now();
+        let result = fetch(self);
+        let latency = started.elapsed();
+        let now = Utc::now();
+        match &result {
+            Ok(_) => self.health.record_success(venue, subject, latency, now),
+            Err(err) if err.is::<BudgetExhausted>() => self.health.record_skipped(venue, now),
+            Err(err) => {
+                self.health
+                    .record_failure(venue, subject, format!("{:#}", err), latency, now)
+            }
+        }
+        result
+    }
+
+    /// Records that `field` of a snapshot could not be fetched; the rest
+    /// of the snapshot is still served.
+    fn note_partial(&self, venue: &str, symbol: &str, field: &str, err: anyhow::Error) {
+        self.partial.borrow_mut().push(format!(
+            "{} {} {} unavailable ({:#})",
+            venue, symbol, field, err
+        ));
+    }
+
+    fn catalog_contract(&self, venue: &str, asset: &str, symbol: &str) -> Option<&FutureContract> {
+        self.catalogs
+            .get(&cache_key(venue, asset))?
+            .contracts
+            .iter()
+            .find(|contract| contract.symbol == symbol)
+    }
+
+    fn fetch_snapshot(&self, venue: &str, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
+        let listed = self
+            .directory
+            .find(venue, symbol)
+            .filter(|info| info.kind == InstrumentKind::Future)
+            .and_then(|info| {
+                Some(FutureContract {
+                    venue: info.venue.clone(),
+                    asset: info.asset.clone(),
+                    symbol: info.symbol.clone(),
+                    expiry: info.expiry?,
+                })
+            });
+        if let Some(contract) = self
+            .catalog_contract(venue, asset, symbol)
+            .or(listed.as_ref())
+        {
+            return match venue {
+                "Deribit" => self.fetch_deribit_future(contract),
+                "Binance" => self.fetch_binance_future(contract),
+                _ => Err(anyhow!("unsupported futures venue: {}", venue)),
+            };
+        }
+
+        match venue {
+            "Bitfinex" if symbol == asset::bitfinex_perp(asset) => {
+                self.fetch_bitfinex_perp(asset, symbol)
+            }
+            "Bitfinex" => self.fetch_bitfinex_spot(asset, symbol),
+            "Deribit" if symbol == asset::deribit_index_symbol(asset) => {
+                self.fetch_deribit_index(asset, symbol)
+            }
+            "Deribit" if symbol.ends_with("PERPETUAL") => self.fetch_deribit_perp(asset, symbol),
+            "Deribit" if parse_deribit_option(symbol).is_some() => {
+                self.fetch_deribit_option(asset, symbol)
+            }
+            "Binance" if symbol == asset::binance_pair(asset) => {
+                self.fetch_binance_perp(asset, symbol)
+            }
             _ => Err(anyhow!(
                 "unsupported venue/symbol combination: {} {}",
                 venue,
@@ -177,12 +692,66 @@ impl DataHub {
         }
     }
 
-    fn fetch_bitfinex_spot(&self, symbol: &str) -> Result<MarketSnapshot> {
+    /// Sends a GET through the venue's rate budget, feeding its rate-limit
+    /// headers back and turning throttling responses into errors.
+    fn get(&self, venue: &str, url: &str) -> Result<Response> {
+        self.limiter.borrow_mut().acquire(venue, url)?;
+        let response = self.http.get(url).send()?;
+        let status = response.status();
+        self.limiter
+            .borrow_mut()
+            .observe(venue, status, response.headers());
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            return Err(anyhow!("{} throttled us (HTTP {})", venue, status.as_u16()));
+        }
+        Ok(response)
+    }
+
+    fn fetch_bitfinex_listing(&self) -> Result<Vec<InstrumentInfo>> {
+        let fetch = |list: &str| -> Result<Vec<String>> {
+            let url = format!(
+                "https://api-pub.bitfinex.com/v2/conf/pub:list:pair:{}",
+                list
+            );
+            let payload: Vec<Vec<String>> = self
+                .get("Bitfinex", &url)
+                .context("bitfinex conf request failed")?
+                .json()
+                .context("bitfinex conf parse failed")?;
+            Ok(payload.into_iter().flatten().collect())
+        };
+
+        let spot = fetch("exchange")?
+            .into_iter()
+            .map(|pair| format!("t{}", pair))
+            .filter_map(|symbol| {
+                let asset = asset::asset_of("Bitfinex", &symbol)?;
+                (!asset.contains(':')).then_some((asset, symbol))
+            })
+            .map(|(asset, symbol)| InstrumentInfo {
+                venue: "Bitfinex".into(),
+                symbol,
+                asset,
+                kind: InstrumentKind::Spot,
+                expiry: None,
+            });
+        let perps = fetch("futures")?
+            .into_iter()
+            .filter_map(|pair| Some((pair.strip_suffix("F0:USTF0")?.to_string(), pair)))
+            .map(|(asset, pair)| InstrumentInfo {
+                venue: "Bitfinex".into(),
+                symbol: format!("t{}", pair),
+                asset,
+                kind: InstrumentKind::Perp,
+                expiry: None,
+            });
+        Ok(spot.chain(perps).collect())
+    }
+
+    fn fetch_bitfinex_spot(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
         let url = format!("https://api-pub.bitfinex.com/v2/ticker/{}", symbol);
         let data: Vec<f64> = self
-            .http
-            .get(url)
-            .send()
+            .get("Bitfinex", &url)
             .context("bitfinex spot request failed")?
             .json()
             .context("bitfinex spot payload parse failed")?;
@@ -190,26 +759,32 @@ impl DataHub {
         let last_price = *data
             .get(6)
             .context("bitfinex ticker missing last price field")?;
+        let volume_24h = data.get(7).map(|volume| volume * last_price);
 
         Ok(MarketSnapshot {
             venue: "Bitfinex".into(),
             instrument_label: "Spot".into(),
             symbol: symbol.into(),
+            asset: asset.into(),
+            kind: InstrumentKind::Spot,
             spot_price: last_price,
             perp_price: None,
+            index_price: None,
+            mark_price: None,
             funding_rate: 0.0,
             predicted_funding_rate: None,
             next_funding_time: None,
+            expiry: None,
+            open_interest: None,
+            volume_24h,
             last_updated: Utc::now(),
         })
     }
 
-    fn fetch_bitfinex_perp(&self, symbol: &str) -> Result<MarketSnapshot> {
+    fn fetch_bitfinex_perp(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
         let ticker_url = format!("https://api-pub.bitfinex.com/v2/ticker/{}", symbol);
         let ticker: Vec<f64> = self
-            .http
-            .get(ticker_url)
-            .send()
+            .get("Bitfinex", &ticker_url)
             .context("bitfinex perp ticker request failed")?
             .json()
             .context("bitfinex perp ticker parse failed")?;
@@ -223,9 +798,7 @@ impl DataHub {
             symbol
         );
         let status_payload: Vec<Vec<Value>> = self
-            .http
-            .get(status_url)
-            .send()
+            .get("Bitfinex", &status_url)
             .context("bitfinex deriv status request failed")?
             .json()
             .context("bitfinex deriv status parse failed")?;
@@ -246,26 +819,40 @@ impl DataHub {
             .get(3)
             .and_then(|value| value.as_f64())
             .unwrap_or(last_price);
+        let index_price = entry.get(4).and_then(|value| value.as_f64());
+        let open_interest = entry
+            .get(18)
+            .and_then(|value| value.as_f64())
+            .map(|contracts| contracts * mark_price);
+        let volume_24h = ticker.get(7).map(|volume| volume * last_price);
 
         Ok(MarketSnapshot {
             venue: "Bitfinex".into(),
             instrument_label: "Perp".into(),
             symbol: symbol.into(),
+            asset: asset.into(),
+            kind: InstrumentKind::Perp,
             spot_price: mark_price,
             perp_price: Some(last_price),
+            index_price,
+            mark_price: Some(mark_price),
             funding_rate,
             predicted_funding_rate,
             next_funding_time,
+            expiry: None,
+            open_interest,
+            volume_24h,
             last_updated: Utc::now(),
         })
     }
 
-    fn fetch_deribit_index(&self, symbol: &str) -> Result<MarketSnapshot> {
-        let url = "https://www.deribit.com/api/v2/public/get_index_price?index_name=btc_usd";
+    fn fetch_deribit_index(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/get_index_price?index_name={}",
+            asset::deribit_index_name(asset)
+        );
         let resp: DeribitIndexResponse = self
-            .http
-            .get(url)
-            .send()
+            .get("Deribit", &url)
             .context("deribit index request failed")?
             .json()
             .context("deribit index parse failed")?;
@@ -274,30 +861,35 @@ impl DataHub {
         let updated_at = result
             .timestamp
             .and_then(ms_to_datetime)
-            .unwrap_or_else(|| Utc::now());
+            .unwrap_or_else(Utc::now);
 
         Ok(MarketSnapshot {
             venue: "Deribit".into(),
             instrument_label: "Index".into(),
             symbol: symbol.into(),
+            asset: asset.into(),
+            kind: InstrumentKind::Index,
             spot_price: result.index_price,
             perp_price: None,
+            index_price: Some(result.index_price),
+            mark_price: None,
             funding_rate: 0.0,
             predicted_funding_rate: None,
             next_funding_time: None,
+            expiry: None,
+            open_interest: None,
+            volume_24h: None,
             last_updated: updated_at,
         })
     }
 
-    fn fetch_deribit_perp(&self, symbol: &str) -> Result<MarketSnapshot> {
+    fn fetch_deribit_perp(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
         let url = format!(
             "https://www.deribit.com/api/v2/public/ticker?instrument_name={}",
             symbol
         );
         let resp: DeribitTickerResponse = self
-            .http
-            .get(url)
-            .send()
+            .get("Deribit", &url)
             .context("deribit ticker request failed")?
             .json()
             .context("deribit ticker parse failed")?;
@@ -313,21 +905,412 @@ impl DataHub {
         let last_updated = result
             .timestamp
             .and_then(ms_to_datetime)
-            .unwrap_or_else(|| Utc::now());
+            .unwrap_or_else(Utc::now);
         let next_funding = result.next_funding_time.and_then(ms_to_datetime);
 
         Ok(MarketSnapshot {
             venue: "Deribit".into(),
             instrument_label: "Perp".into(),
             symbol: symbol.into(),
+            asset: asset.into(),
+            kind: InstrumentKind::Perp,
             spot_price,
             perp_price: Some(mark_price),
+            index_price,
+            mark_price: result.mark_price,
             funding_rate: result.current_funding.unwrap_or(0.0),
             predicted_funding_rate: result.funding_8h,
             next_funding_time: next_funding,
+            expiry: None,
+            open_interest: deribit_open_interest_usd(asset, result.open_interest, mark_price),
+            volume_24h: result.stats.volume_usd,
+            last_updated,
+        })
+    }
+
+    fn fetch_deribit_futures(&self, asset: &str) -> Result<Vec<FutureContract>> {
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/get_instruments?currency={}&kind=future&expired=false",
+            asset::deribit_currency(asset)
+        );
+        let resp: DeribitInstrumentsResponse = self
+            .get("Deribit", &url)
+            .context("deribit instruments request failed")?
+            .json()
+            .context("deribit instruments parse failed")?;
+
+        Ok(resp
+            .result
+            .into_iter()
+            .filter(|instrument| instrument.settlement_period != "perpetual")
+            .filter(|instrument| instrument.base_currency == asset)
+            .filter_map(|instrument| {
+                Some(FutureContract {
+                    venue: "Deribit".into(),
+                    asset: asset.into(),
+                    expiry: ms_to_datetime(instrument.expiration_timestamp)?,
+                    symbol: instrument.instrument_name,
+                })
+            })
+            .collect())
+    }
+
+    fn fetch_deribit_listing(&self) -> Result<Vec<InstrumentInfo>> {
+        let url =
+            "https://www.deribit.com/api/v2/public/get_instruments?currency=any&expired=false";
+        let resp: DeribitInstrumentsResponse = self
+            .get("Deribit", url)
+            .context("deribit instruments request failed")?
+            .json()
+            .context("deribit instruments parse failed")?;
+
+        // Combos are skipped: they have no ticker of their own.
+        Ok(resp
+            .result
+            .into_iter()
+            .filter_map(|instrument| {
+                let is_perp = instrument.settlement_period == "perpetual";
+                let kind = match instrument.kind.as_str() {
+                    "future" if is_perp => InstrumentKind::Perp,
+                    "future" => InstrumentKind::Future,
+                    "option" => InstrumentKind::Option,
+                    _ => return None,
+                };
+                Some(InstrumentInfo {
+                    venue: "Deribit".into(),
+                    asset: instrument.base_currency,
+                    kind,
+                    expiry: (!is_perp)
+                        .then(|| ms_to_datetime(instrument.expiration_timestamp))
+                        .flatten(),
+                    symbol: instrument.instrument_name,
+                })
+            })
+            .collect())
+    }
+
+    fn fetch_deribit_future(&self, contract: &FutureContract) -> Result<MarketSnapshot> {
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/ticker?instrument_name={}",
+            contract.symbol
+        );
+        let resp: DeribitTickerResponse = self
+            .get("Deribit", &url)
+            .context("deribit future ticker request failed")?
+            .json()
+            .context("deribit future ticker parse failed")?;
+
+        let result = resp.result;
+        let index_price = result.index_price;
+        let mark_price = result
+            .mark_price
+            .or(result.last_price)
+            .context("deribit future ticker missing price")?;
+        let last_updated = result
+            .timestamp
+            .and_then(ms_to_datetime)
+            .unwrap_or_else(Utc::now);
+
+        Ok(MarketSnapshot {
+            venue: "Deribit".into(),
+            instrument_label: "Future".into(),
+            symbol: contract.symbol.clone(),
+            asset: contract.asset.clone(),
+            kind: InstrumentKind::Future,
+            spot_price: index_price.unwrap_or(mark_price),
+            perp_price: Some(mark_price),
+            index_price,
+            mark_price: result.mark_price,
+            funding_rate: 0.0,
+            predicted_funding_rate: None,
+            next_funding_time: None,
+            expiry: Some(contract.expiry),
+            open_interest: deribit_open_interest_usd(
+                &contract.asset,
+                result.open_interest,
+                mark_price,
+            ),
+            volume_24h: result.stats.volume_usd,
             last_updated,
         })
     }
+
+    /// A single watched option. Inverse options quote their premium in the
+    /// underlying, so it is converted at the index to compare in USD.
+    fn fetch_deribit_option(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
+        let (expiry, _, _) =
+            parse_deribit_option(symbol).context("deribit option symbol not recognized")?;
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/ticker?instrument_name={}",
+            symbol
+        );
+        let resp: DeribitTickerResponse = self
+            .get("Deribit", &url)
+            .context("deribit option ticker request failed")?
+            .json()
+            .context("deribit option ticker parse failed")?;
+
+        let result = resp.result;
+        let index_price = result
+            .index_price
+            .context("deribit option ticker missing index")?;
+        let mark = result
+            .mark_price
+            .or(result.last_price)
+            .context("deribit option ticker missing price")?;
+        let premium = if asset::deribit_is_inverse(asset) {
+            mark * index_price
+        } else {
+            mark
+        };
+        let last_updated = result
+            .timestamp
+            .and_then(ms_to_datetime)
+            .unwrap_or_else(Utc::now);
+
+        // No perp or mark leg: an option has no basis or premium to index.
+        Ok(MarketSnapshot {
+            venue: "Deribit".into(),
+            instrument_label: "Option".into(),
+            symbol: symbol.to_string(),
+            asset: asset.to_string(),
+            kind: InstrumentKind::Option,
+            spot_price: premium,
+            perp_price: None,
+            index_price: Some(index_price),
+            mark_price: None,
+            funding_rate: 0.0,
+            predicted_funding_rate: None,
+            next_funding_time: None,
+            expiry: Some(expiry),
+            open_interest: result.open_interest,
+            volume_24h: result.stats.volume_usd,
+            last_updated,
+        })
+    }
+
+    fn fetch_deribit_options(&self, asset: &str) -> Result<OptionChain> {
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/get_book_summary_by_currency?currency={}&kind=option",
+            asset::deribit_currency(asset)
+        );
+        let resp: DeribitBookSummaryResponse = self
+            .get("Deribit", &url)
+            .context("deribit option summary request failed")?
+            .json()
+            .context("deribit option summary parse failed")?;
+
+        let quotes = resp
+            .result
+            .into_iter()
+            .filter(|entry| {
+                asset::asset_of("Deribit", &entry.instrument_name).as_deref() == Some(asset)
+            })
+            .filter_map(|entry| {
+                let (expiry, strike, is_call) = parse_deribit_option(&entry.instrument_name)?;
+                Some(OptionQuote {
+                    symbol: entry.instrument_name,
+                    expiry,
+                    strike,
+                    is_call,
+                    mark_iv: entry.mark_iv? / 100.0,
+                    underlying_price: entry.underlying_price?,
+                    open_interest: entry.open_interest,
+                })
+            })
+            .collect();
+
+        Ok(OptionChain {
+            venue: "Deribit".into(),
+            asset: asset.into(),
+            quotes,
+            volatility_index: asset::deribit_is_inverse(asset)
+                .then(|| {
+                    self.fetch_deribit_dvol(asset)
+                        .map_err(|err| self.note_partial("Deribit", asset, "DVOL", err))
+                        .ok()
+                })
+                .flatten(),
+            fetched_at: Utc::now(),
+        })
+    }
+
+    /// DVOL only exists for the inverse (BTC, ETH) currencies.
+    fn fetch_deribit_dvol(&self, asset: &str) -> Result<f64> {
+        let end = Utc::now();
+        let start = end - ChronoDuration::hours(2);
+        let url = format!(
+            "https://www.deribit.com/api/v2/public/get_volatility_index_data?currency={}&resolution=60&start_timestamp={}&end_timestamp={}",
+            asset,
+            start.timestamp_millis(),
+            end.timestamp_millis()
+        );
+        let resp: DeribitVolIndexResponse = self
+            .get("Deribit", &url)
+            .context("deribit dvol request failed")?
+            .json()
+            .context("deribit dvol parse failed")?;
+
+        let close = resp
+            .result
+            .data
+            .last()
+            .and_then(|candle| candle.get(4).copied())
+            .context("deribit dvol returned no candles")?;
+        Ok(close / 100.0)
+    }
+
+    fn fetch_binance_perp(&self, asset: &str, symbol: &str) -> Result<MarketSnapshot> {
+        let premium = self.fetch_binance_premium(symbol)?;
+        let mark_price = parse_decimal(&premium.mark_price)?;
+        let index_price = parse_decimal(&premium.index_price)?;
+        let open_interest = self
+            .fetch_binance_open_interest(symbol)
+            .map(|contracts| contracts * mark_price)
+            .map_err(|err| self.note_partial("Binance", symbol, "open interest", err))
+            .ok();
+        let volume_24h = self
+            .fetch_binance_quote_volume(symbol)
+            .map_err(|err| self.note_partial("Binance", symbol, "volume", err))
+            .ok();
+
+        Ok(MarketSnapshot {
+            venue: "Binance".into(),
+            instrument_label: "Perp".into(),
+            symbol: symbol.into(),
+            asset: asset.into(),
+            kind: InstrumentKind::Perp,
+            spot_price: index_price,
+            perp_price: Some(mark_price),
+            index_price: Some(index_price),
+            mark_price: Some(mark_price),
+            funding_rate: parse_decimal(&premium.last_funding_rate).unwrap_or(0.0),
+            predicted_funding_rate: None,
+            next_funding_time: premium.next_funding_time.and_then(ms_to_datetime),
+            expiry: None,
+            open_interest,
+            volume_24h,
+            last_updated: ms_to_datetime(premium.time).unwrap_or_else(Utc::now),
+        })
+    }
+
+    fn fetch_binance_futures(&self, asset: &str) -> Result<Vec<FutureContract>> {
+        let pair = asset::binance_pair(asset);
+        let url = "https://fapi.binance.com/fapi/v1/exchangeInfo";
+        let resp: BinanceExchangeInfo = self
+            .get("Binance", url)
+            .context("binance exchange info request failed")?
+            .json()
+            .context("binance exchange info parse failed")?;
+
+        Ok(resp
+            .symbols
+            .into_iter()
+            .filter(|info| info.pair == pair && info.status == "TRADING")
+            .filter(|info| info.contract_type.ends_with("QUARTER"))
+            .filter_map(|info| {
+                Some(FutureContract {
+                    venue: "Binance".into(),
+                    asset: asset.into(),
+                    expiry: ms_to_datetime(info.delivery_date)?,
+                    symbol: info.symbol,
+                })
+            })
+            .collect())
+    }
+
+    fn fetch_binance_listing(&self) -> Result<Vec<InstrumentInfo>> {
+        let url = "https://fapi.binance.com/fapi/v1/exchangeInfo";
+        let resp: BinanceExchangeInfo = self
+            .get("Binance", url)
+            .context("binance exchange info request failed")?
+            .json()
+            .context("binance exchange info parse failed")?;
+
+        Ok(resp
+            .symbols
+            .into_iter()
+            .filter(|info| info.status == "TRADING" && info.quote_asset == "USDT")
+            .filter_map(|info| {
+                let (kind, expiry) = match info.contract_type.as_str() {
+                    "PERPETUAL" => (InstrumentKind::Perp, None),
+                    contract if contract.ends_with("QUARTER") => {
+                        (InstrumentKind::Future, ms_to_datetime(info.delivery_date))
+                    }
+                    _ => return None,
+                };
+                Some(InstrumentInfo {
+                    venue: "Binance".into(),
+                    symbol: info.symbol,
+                    asset: info.base_asset,
+                    kind,
+                    expiry,
+                })
+            })
+            .collect())
+    }
+
+    fn fetch_binance_future(&self, contract: &FutureContract) -> Result<MarketSnapshot> {
+        let premium = self.fetch_binance_premium(&contract.symbol)?;
+        let mark_price = parse_decimal(&premium.mark_price)?;
+        let index_price = parse_decimal(&premium.index_price)?;
+
+        Ok(MarketSnapshot {
+            venue: "Binance".into(),
+            instrument_label: "Future".into(),
+            symbol: contract.symbol.clone(),
+            asset: contract.asset.clone(),
+            kind: InstrumentKind::Future,
+            spot_price: index_price,
+            perp_price: Some(mark_price),
+            index_price: Some(index_price),
+            mark_price: Some(mark_price),
+            funding_rate: 0.0,
+            predicted_funding_rate: None,
+            next_funding_time: None,
+            expiry: Some(contract.expiry),
+            open_interest: None,
+            volume_24h: None,
+            last_updated: ms_to_datetime(premium.time).unwrap_or_else(Utc::now),
+        })
+    }
+
+    fn fetch_binance_open_interest(&self, symbol: &str) -> Result<f64> {
+        let url = format!(
+            "https://fapi.binance.com/fapi/v1/openInterest?symbol={}",
+            symbol
+        );
+        let resp: BinanceOpenInterest = self
+            .get("Binance", &url)
+            .context("binance open interest request failed")?
+            .json()
+            .context("binance open interest parse failed")?;
+        parse_decimal(&resp.open_interest)
+    }
+
+    fn fetch_binance_quote_volume(&self, symbol: &str) -> Result<f64> {
+        let url = format!(
+            "https://fapi.binance.com/fapi/v1/ticker/24hr?symbol={}",
+            symbol
+        );
+        let resp: Binance24hTicker = self
+            .get("Binance", &url)
+            .context("binance 24h ticker request failed")?
+            .json()
+            .context("binance 24h ticker parse failed")?;
+        parse_decimal(&resp.quote_volume)
+    }
+
+    fn fetch_binance_premium(&self, symbol: &str) -> Result<BinancePremiumIndex> {
+        let url = format!(
+            "https://fapi.binance.com/fapi/v1/premiumIndex?symbol={}",
+            symbol
+        );
+        self.get("Binance", &url)
+            .context("binance premium index request failed")?
+            .json()
+            .context("binance premium index parse failed")
+    }
 }
 
 fn cache_key(venue: &str, symbol: &str) -> String {
@@ -338,6 +1321,40 @@ fn ms_to_datetime(ms: i64) -> Option<DateTime<Utc>> {
     DateTime::<Utc>::from_timestamp_millis(ms)
 }
 
+/// Inverse contracts report open interest in USD; linear USDC ones in coins.
+fn deribit_open_interest_usd(
+    asset: &str,
+    open_interest: Option<f64>,
+    mark_price: f64,
+) -> Option<f64> {
+    if asset::deribit_is_inverse(asset) {
+        open_interest
+    } else {
+        open_interest.map(|coins| coins * mark_price)
+    }
+}
+
+/// Splits `BTC-27JUN25-70000-C` into expiry (08:00 UTC), strike and side.
+fn parse_deribit_option(name: &str) -> Option<(DateTime<Utc>, f64, bool)> {
+    let mut parts = name.split('-').skip(1);
+    let expiry_code = parts.next()?;
+    let strike = parts.next()?.replace('d', ".").parse::<f64>().ok()?;
+    let is_call = match parts.next()? {
+        "C" => true,
+        "P" => false,
+        _ => return None,
+    };
+
+    let date = chrono::NaiveDate::parse_from_str(expiry_code, "%d%b%y").ok()?;
+    let expiry = date.and_hms_opt(8, 0, 0)?.and_utc();
+    Some((expiry, strike, is_call))
+}
+
+fn parse_decimal(raw: &str) -> Result<f64> {
+    raw.parse::<f64>()
+        .with_context(|| format!("invalid decimal field {:?}", raw))
+}
+
 #[derive(Debug, Deserialize)]
 struct DeribitIndexResponse {
     result: DeribitIndexResult,
@@ -365,4 +1382,137 @@ struct DeribitTickerResult {
     funding_8h: Option<f64>,
     next_funding_time: Option<i64>,
     timestamp: Option<i64>,
+    open_interest: Option<f64>,
+    stats: DeribitTickerStats,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct DeribitTickerStats {
+    volume_usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitInstrumentsResponse {
+    result: Vec<DeribitInstrument>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitInstrument {
+    instrument_name: String,
+    expiration_timestamp: i64,
+    #[serde(default)]
+    settlement_period: String,
+    #[serde(default)]
+    base_currency: String,
+    #[serde(default)]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinancePremiumIndex {
+    mark_price: String,
+    index_price: String,
+    #[serde(default)]
+    last_funding_rate: String,
+    next_funding_time: Option<i64>,
+    time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceExchangeInfo {
+    symbols: Vec<BinanceSymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceSymbolInfo {
+    symbol: String,
+    pair: String,
+    contract_type: String,
+    delivery_date: i64,
+    status: String,
+    #[serde(default)]
+    base_asset: String,
+    #[serde(default)]
+    quote_asset: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceOpenInterest {
+    open_interest: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Binance24hTicker {
+    quote_volume: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitBookSummaryResponse {
+    result: Vec<DeribitBookSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitBookSummary {
+    instrument_name: String,
+    mark_iv: Option<f64>,
+    underlying_price: Option<f64>,
+    open_interest: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitVolIndexResponse {
+    result: DeribitVolIndexResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitVolIndexResult {
+    data: Vec<Vec<f64>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deribit_linear_open_interest_is_priced_in_usd() {
+        assert_eq!(
+            deribit_open_interest_usd("BTC", Some(1_000_000.0), 60_000.0),
+            Some(1_000_000.0)
+        );
+        assert_eq!(
+            deribit_open_interest_usd("SOL", Some(2_500.0), 150.0),
+            Some(375_000.0)
+        );
+        assert_eq!(deribit_open_interest_usd("SOL", None, 150.0), None);
+    }
+
+    #[test]
+    fn deribit_option_names_parse() {
+        let (expiry, strike, is_call) = parse_deribit_option("BTC-27DEC24-60000-C").unwrap();
+        assert_eq!(expiry.to_rfc3339(), "2024-12-27T08:00:00+00:00");
+        assert_eq!(strike, 60_000.0);
+        assert!(is_call);
+
+        let (_, strike, is_call) = parse_deribit_option("XRP_USDC-7JUN24-0d625-P").unwrap();
+        assert_eq!(strike, 0.625);
+        assert!(!is_call);
+    }
+
+    #[test]
+    fn malformed_deribit_option_names_are_rejected() {
+        for name in [
+            "BTC-PERPETUAL",
+            "BTC-27DEC24",
+            "BTC-27DEC24-60000-X",
+            "BTC-31FEB24-60000-C",
+            "BTC-27DEC24-abc-C",
+        ] {
+            assert!(parse_deribit_option(name).is_none(), "{name}");
+        }
+    }
 }
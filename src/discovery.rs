@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::data::InstrumentKind;
+use crate::persist;
+
+/// Venue listings change rarely; refresh the local copy daily.
+const DIRECTORY_TTL_HOURS: i64 = 24;
+
+/// One tradable instrument from a venue listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentInfo {
+    pub venue: String,
+    pub symbol: String,
+    pub asset: String,
+    pub kind: InstrumentKind,
+    pub expiry: Option<DateTime<Utc>>,
+}
+
+impl InstrumentInfo {
+    fn haystack(&self) -> String {
+        format!(
+            "{} {} {} {:?}",
+            self.venue, self.symbol, self.asset, self.kind
+        )
+        .to_lowercase()
+    }
+}
+
+/// Every venue's listing, cached locally between refreshes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstrumentDirectory {
+    pub instruments: Vec<InstrumentInfo>,
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl InstrumentDirectory {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(persist::load_json(path, "instrument directory")?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        persist::save_json(path, self, "instrument directory")
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.fetched_at
+            .is_none_or(|at| Utc::now() - at >= Duration::hours(DIRECTORY_TTL_HOURS))
+    }
+
+    /// Replaces one venue's listing, keeping the others.
+    pub fn replace_venue(&mut self, venue: &str, instruments: Vec<InstrumentInfo>) {
+        self.instruments.retain(|info| info.venue != venue);
+        self.instruments.extend(instruments);
+    }
+
+    pub fn find(&self, venue: &str, symbol: &str) -> Option<&InstrumentInfo> {
+        self.instruments
+            .iter()
+            .find(|info| info.venue == venue && info.symbol == symbol)
+    }
+
+    /// Instruments matching every whitespace-separated term of `query`
+    /// (case-insensitive, against venue, symbol, asset and kind). Exact
+    /// asset or symbol hits rank first, then perps, then shorter symbols.
+    pub fn search(&self, query: &str) -> Vec<&InstrumentInfo> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect();
+        let mut matches: Vec<&InstrumentInfo> = self
+            .instruments
+            .iter()
+            .filter(|info| {
+                let haystack = info.haystack();
+                terms.iter().all(|term| haystack.contains(term.as_str()))
+            })
+            .collect();
+        matches.sort_by_key(|info| {
+            let exact = terms.iter().any(|term| {
+                info.asset.eq_ignore_ascii_case(term) || info.symbol.eq_ignore_ascii_case(term)
+            });
+            (
+                !exact,
+                info.kind != InstrumentKind::Perp,
+                info.symbol.len(),
+                info.symbol.clone(),
+            )
+        });
+        matches
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchEntry {
+    pub venue: String,
+    pub symbol: String,
+}
+
+/// Instruments added from the picker, persisted so they survive restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub entries: Vec<WatchEntry>,
+}
+
+impl Watchlist {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(persist::load_json(path, "watchlist")?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        persist::save_json(path, self, "watchlist")
+    }
+
+    /// Adds the entry unless present; returns whether it was new.
+    pub fn add(&mut self, venue: &str, symbol: &str) -> bool {
+        let entry = WatchEntry {
+            venue: venue.to_string(),
+            symbol: symbol.to_string(),
+        };
+        if self.entries.contains(&entry) {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+}
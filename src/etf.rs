@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::data::MarketSnapshot;
+
+pub const ETF_VENUE: &str = "ETF";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EtfConfig {
+    pub tickers: Vec<String>,
+    /// CSV of `ticker,shares_outstanding,btc_per_share`.
+    pub holdings_path: PathBuf,
+    pub quotes: EtfQuoteSourceConfig,
+    /// Snapshot the implied NAV is priced from.
+    pub index_venue: String,
+    pub index_symbol: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum EtfQuoteSourceConfig {
+    /// CSV of `ticker,price`.
+    Csv { path: PathBuf },
+    /// `url` with a `{ticker}` placeholder returning `{"price": 51.23}`.
+    Http { url: String },
+}
+
+/// Somewhere ETF share prices can be read from.
+pub trait EtfQuoteSource {
+    fn quote(&self, ticker: &str) -> Result<f64>;
+}
+
+pub struct CsvQuoteSource {
+    path: PathBuf,
+}
+
+impl EtfQuoteSource for CsvQuoteSource {
+    fn quote(&self, ticker: &str) -> Result<f64> {
+        let rows = read_csv(&self.path)?;
+        let row = rows
+            .iter()
+            .find(|row| {
+                row.first()
+                    .is_some_and(|cell| cell.eq_ignore_ascii_case(ticker))
+            })
+            .with_context(|| format!("{} missing from {}", ticker, self.path.display()))?;
+        parse_cell(row, 1, "price")
+    }
+}
+
+pub struct HttpQuoteSource {
+    http: Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpQuote {
+    price: f64,
+}
+
+impl EtfQuoteSource for HttpQuoteSource {
+    fn quote(&self, ticker: &str) -> Result<f64> {
+        let url = self.url.replace("{ticker}", ticker);
+        let quote: HttpQuote = self
+            .http
+            .get(url)
+            .send()
+            .context("etf quote request failed")?
+            .json()
+            .context("etf quote parse failed")?;
+        Ok(quote.price)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EtfHolding {
+    pub shares_outstanding: f64,
+    pub btc_per_share: f64,
+}
+
+/// ETF share price against the BTC it holds.
+#[derive(Debug, Clone)]
+pub struct EtfValuation {
+    pub ticker: String,
+    /// Underlying of the index the NAV is priced from.
+    pub asset: String,
+    pub price: f64,
+    pub nav_per_share: f64,
+    pub premium_bps: f64,
+    pub btc_holdings: f64,
+    pub quoted_at: DateTime<Utc>,
+}
+
+impl EtfValuation {
+    /// ETF row for the table and alerts; mark is the share price and index
+    /// the implied NAV, so `premium` reads straight off the snapshot.
+    pub fn to_snapshot(&self) -> MarketSnapshot {
+        let mut snapshot = MarketSnapshot::placeholder(
+            ETF_VENUE,
+            "ETF",
+            self.ticker.clone(),
+            self.price,
+            None,
+            0.0,
+            None,
+            None,
+        );
+        snapshot.asset = self.asset.clone();
+        snapshot.index_price = Some(self.nav_per_share);
+        snapshot.mark_price = Some(self.price);
+        snapshot.last_updated = self.quoted_at;
+        snapshot
+    }
+}
+
+pub struct EtfTracker {
+    config: EtfConfig,
+    source: Box<dyn EtfQuoteSource>,
+    quotes: HashMap<String, (f64, DateTime<Utc>)>,
+}
+
+impl EtfTracker {
+    pub fn new(config: EtfConfig) -> Self {
+        let source: Box<dyn EtfQuoteSource> = match &config.quotes {
+            EtfQuoteSourceConfig::Csv { path } => Box::new(CsvQuoteSource { path: path.clone() }),
+            EtfQuoteSourceConfig::Http { url } => Box::new(HttpQuoteSource {
+                http: Client::new(),
+                url: url.clone(),
+            }),
+        };
+        Self::with_source(config, source)
+    }
+
+    pub fn with_source(config: EtfConfig, source: Box<dyn EtfQuoteSource>) -> Self {
+        Self {
+            config,
+            source,
+            quotes: HashMap::new(),
+        }
+    }
+
+    /// Values every configured ticker against the index snapshot. Quotes are
+    /// re-read once older than `ttl`; failures fall back to the last quote.
+    pub fn collect(
+        &mut self,
+        snapshots: &[MarketSnapshot],
+        ttl: Duration,
+    ) -> (Vec<EtfValuation>, Vec<String>) {
+        // No holdings file yet means ETF tracking simply isn't set up.
+        if !self.config.holdings_path.exists() {
+            return (Vec::new(), Vec::new());
+        }
+
+        let mut warnings = Vec::new();
+        let Some(index) = snapshots
+            .iter()
+            .find(|s| s.venue == self.config.index_venue && s.symbol == self.config.index_symbol)
+        else {
+            warnings.push(format!(
+                "ETF NAV skipped: {} {} index unavailable",
+                self.config.index_venue, self.config.index_symbol
+            ));
+            return (Vec::new(), warnings);
+        };
+
+        let holdings = match load_holdings(&self.config.holdings_path) {
+            Ok(holdings) => holdings,
+            Err(err) => {
+                warnings.push(format!("{:#}", err));
+                return (Vec::new(), warnings);
+            }
+        };
+
+        let now = Utc::now();
+        let mut valuations = Vec::new();
+        for ticker in &self.config.tickers {
+            let Some(holding) = holdings.get(&ticker.to_uppercase()) else {
+                warnings.push(format!("ETF {} has no holdings entry", ticker));
+                continue;
+            };
+
+            let cached = self.quotes.get(ticker).copied();
+            let quote = match cached {
+                Some((price, at)) if now - at < ttl => Some((price, at)),
+                _ => match self.source.quote(ticker) {
+                    Ok(price) => {
+                        self.quotes.insert(ticker.clone(), (price, now));
+                        Some((price, now))
+                    }
+                    Err(err) => {
+                        warnings.push(format!("ETF {} quote failed ({:#})", ticker, err));
+                        cached
+                    }
+                },
+            };
+            let Some((price, quoted_at)) = quote else {
+                continue;
+            };
+
+            let nav_per_share = holding.btc_per_share * index.reference_price();
+            if nav_per_share <= 0.0 {
+                continue;
+            }
+            valuations.push(EtfValuation {
+                ticker: ticker.clone(),
+                asset: index.asset.clone(),
+                price,
+                nav_per_share,
+                premium_bps: (price / nav_per_share - 1.0) * 10_000.0,
+                btc_holdings: holding.shares_outstanding * holding.btc_per_share,
+                quoted_at,
+            });
+        }
+        (valuations, warnings)
+    }
+}
+
+fn load_holdings(path: &Path) -> Result<HashMap<String, EtfHolding>> {
+    read_csv(path)?
+        .iter()
+        .map(|row| {
+            let ticker = row.first().context("holdings row missing ticker")?;
+            Ok((
+                ticker.to_uppercase(),
+                EtfHolding {
+                    shares_outstanding: parse_cell(row, 1, "shares_outstanding")?,
+                    btc_per_share: parse_cell(row, 2, "btc_per_share")?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Rows of a simple comma-separated file, skipping blanks, `#` comments and
+/// a header row whose first cell is `ticker`.
+fn read_csv(path: &Path) -> Result<Vec<Vec<String>>> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split(',')
+                .map(|cell| cell.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row[0].eq_ignore_ascii_case("ticker"))
+        .collect())
+}
+
+fn parse_cell(row: &[String], idx: usize, name: &str) -> Result<f64> {
+    let cell = row
+        .get(idx)
+        .ok_or_else(|| anyhow!("{} row missing {}", row[0], name))?;
+    cell.parse::<f64>()
+        .with_context(|| format!("{} has invalid {} {:?}", row[0], name, cell))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct FixedQuotes {
+        prices: HashMap<String, f64>,
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl EtfQuoteSource for FixedQuotes {
+        fn quote(&self, ticker: &str) -> Result<f64> {
+            self.calls.set(self.calls.get() + 1);
+            self.prices
+                .get(ticker)
+                .copied()
+                .with_context(|| format!("no quote for {}", ticker))
+        }
+    }
+
+    fn holdings_file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("quantumdesk-{}-{}.csv", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn tracker(holdings_path: PathBuf, prices: &[(&str, f64)]) -> (EtfTracker, Rc<Cell<usize>>) {
+        let calls = Rc::new(Cell::new(0));
+        let config = EtfConfig {
+            tickers: vec!["IBIT".into(), "FBTC".into()],
+            holdings_path,
+            quotes: EtfQuoteSourceConfig::Csv {
+                path: PathBuf::from("unused.csv"),
+            },
+            index_venue: "Coinbase".into(),
+            index_symbol: "BTC-USD".into(),
+        };
+        let source = FixedQuotes {
+            prices: prices.iter().map(|(t, p)| (t.to_string(), *p)).collect(),
+            calls: Rc::clone(&calls),
+        };
+        (EtfTracker::with_source(config, Box::new(source)), calls)
+    }
+
+    fn index(price: f64) -> MarketSnapshot {
+        let mut snapshot = MarketSnapshot::placeholder(
+            "Coinbase", "Spot", "BTC-USD", price, None, 0.0, None, None,
+        );
+        snapshot.asset = "BTC".into();
+        snapshot
+    }
+
+    #[test]
+    fn missing_holdings_file_is_silent_even_without_an_index() {
+        let path = std::env::temp_dir().join("quantumdesk-no-such-holdings.csv");
+        let (mut tracker, calls) = tracker(path, &[("IBIT", 40.0)]);
+        let (valuations, warnings) = tracker.collect(&[], Duration::minutes(5));
+        assert!(valuations.is_empty());
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn missing_index_is_reported_once_holdings_exist() {
+        let path = holdings_file("no-index", "IBIT,1000,0.0005\n");
+        let (mut tracker, _) = tracker(path.clone(), &[("IBIT", 40.0)]);
+        let (valuations, warnings) = tracker.collect(&[], Duration::minutes(5));
+        fs::remove_file(path).unwrap();
+        assert!(valuations.is_empty());
+        assert_eq!(
+            warnings,
+            ["ETF NAV skipped: Coinbase BTC-USD index unavailable"]
+        );
+    }
+
+    #[test]
+    fn premium_is_price_over_nav_in_bps() {
+        let path = holdings_file(
+            "premium",
+            "ticker,shares_outstanding,btc_per_share\n# comment\nibit,1000,0.0005\nFBTC,200,0.001\n",
+        );
+        let (mut tracker, calls) = tracker(path.clone(), &[("IBIT", 30.15), ("FBTC", 59.4)]);
+        let (valuations, warnings) = tracker.collect(&[index(60_000.0)], Duration::minutes(5));
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        // IBIT NAV 30.00 -> +50 bps; FBTC NAV 60.00 -> -100 bps.
+        let ibit = &valuations[0];
+        assert_eq!(ibit.ticker, "IBIT");
+        assert_eq!(ibit.asset, "BTC");
+        assert!((ibit.nav_per_share - 30.0).abs() < 1e-9);
+        assert!((ibit.premium_bps - 50.0).abs() < 1e-9);
+        assert!((ibit.btc_holdings - 0.5).abs() < 1e-12);
+        assert!((valuations[1].premium_bps + 100.0).abs() < 1e-9);
+
+        // Fresh quotes are served from the cache.
+        tracker.collect(&[index(60_000.0)], Duration::minutes(5));
+        fs::remove_file(path).unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn failed_quotes_and_unknown_tickers_warn() {
+        let path = holdings_file("partial", "IBIT,1000,0.0005\n");
+        let (mut tracker, _) = tracker(path.clone(), &[]);
+        let (valuations, warnings) = tracker.collect(&[index(60_000.0)], Duration::minutes(5));
+        fs::remove_file(path).unwrap();
+        assert!(valuations.is_empty());
+        assert_eq!(
+            warnings,
+            [
+                "ETF IBIT quote failed (no quote for IBIT)",
+                "ETF FBTC has no holdings entry"
+            ]
+        );
+    }
+}
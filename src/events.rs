@@ -0,0 +1,213 @@
+//! Event log: fetch errors, reconnects, alert transitions and config changes,
+//! kept in a bounded ring buffer for the log pane.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Events kept before the oldest are dropped.
+pub const LOG_CAPACITY: usize = 500;
+/// Recent events a repeat of the same message is folded into, so a feed
+/// failing on every tick does not flush the rest of the log.
+const COALESCE_WINDOW: usize = 32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    /// Steps the log filter through info → warn → error → info.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Info => Self::Warn,
+            Self::Warn => Self::Error,
+            Self::Error => Self::Info,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// What an event is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// A venue request, listing or cache fallback.
+    Fetch,
+    /// A venue circuit opening, degrading or recovering.
+    Connection,
+    Alert,
+    Config,
+    /// Reading or writing history, caches and UI state.
+    Storage,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Fetch => "fetch",
+            Self::Connection => "conn",
+            Self::Alert => "alert",
+            Self::Config => "config",
+            Self::Storage => "storage",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    /// Last time the event was recorded.
+    pub at: DateTime<Utc>,
+    pub severity: Severity,
+    pub kind: EventKind,
+    /// Full message, including every cause of an error chain.
+    pub message: String,
+    /// Times the same event was recorded again since it was first logged.
+    pub repeats: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    events: VecDeque<LogEvent>,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::with_capacity(LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Records an event. A repeat of a recent one moves it to the end with
+    /// its count bumped instead of adding another entry.
+    pub fn push(&mut self, severity: Severity, kind: EventKind, message: impl Into<String>) {
+        let message = message.into();
+        let now = Utc::now();
+        let recent = self.events.len().saturating_sub(COALESCE_WINDOW);
+        let repeat = (recent..self.events.len()).rev().find(|idx| {
+            let event = &self.events[*idx];
+            event.severity == severity && event.kind == kind && event.message == message
+        });
+        let event = match repeat.and_then(|idx| self.events.remove(idx)) {
+            Some(event) => LogEvent {
+                at: now,
+                repeats: event.repeats + 1,
+                ..event
+            },
+            None => LogEvent {
+                at: now,
+                severity,
+                kind,
+                message,
+                repeats: 0,
+            },
+        };
+        self.events.push_back(event);
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+    }
+
+    /// Events at `min` severity or above, oldest first.
+    pub fn filtered(&self, min: Severity) -> impl DoubleEndedIterator<Item = &LogEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.severity >= min)
+    }
+
+    /// Events at `min` severity or above recorded since `since`.
+    pub fn count_since(&self, min: Severity, since: DateTime<Utc>) -> usize {
+        self.filtered(min).filter(|event| event.at >= since).count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(log: &EventLog) -> Vec<(&str, u32)> {
+        log.filtered(Severity::Info)
+            .map(|event| (event.message.as_str(), event.repeats))
+            .collect()
+    }
+
+    #[test]
+    fn repeats_fold_into_the_latest_entry() {
+        let mut log = EventLog::default();
+        log.push(Severity::Warn, EventKind::Fetch, "a down");
+        log.push(Severity::Info, EventKind::Config, "loaded");
+        log.push(Severity::Warn, EventKind::Fetch, "a down");
+        assert_eq!(messages(&log), [("loaded", 0), ("a down", 1)]);
+    }
+
+    #[test]
+    fn only_identical_events_fold() {
+        let mut log = EventLog::default();
+        log.push(Severity::Warn, EventKind::Fetch, "a down");
+        log.push(Severity::Error, EventKind::Fetch, "a down");
+        log.push(Severity::Warn, EventKind::Connection, "a down");
+        assert_eq!(log.len(), 3);
+    }
+
+    #[test]
+    fn repeats_outside_the_window_are_logged_again() {
+        let mut log = EventLog::default();
+        log.push(Severity::Warn, EventKind::Fetch, "a down");
+        for index in 0..COALESCE_WINDOW {
+            log.push(Severity::Info, EventKind::Fetch, format!("ok {index}"));
+        }
+        log.push(Severity::Warn, EventKind::Fetch, "a down");
+        assert_eq!(log.len(), COALESCE_WINDOW + 2);
+        assert_eq!(log.filtered(Severity::Warn).count(), 2);
+    }
+
+    #[test]
+    fn oldest_events_drop_past_capacity() {
+        let mut log = EventLog::with_capacity(2);
+        for message in ["one", "two", "three"] {
+            log.push(Severity::Info, EventKind::Config, message);
+        }
+        assert_eq!(messages(&log), [("two", 0), ("three", 0)]);
+    }
+
+    #[test]
+    fn filter_and_count_respect_severity() {
+        let mut log = EventLog::default();
+        let start = Utc::now();
+        log.push(Severity::Info, EventKind::Config, "loaded");
+        log.push(Severity::Warn, EventKind::Fetch, "slow");
+        log.push(Severity::Error, EventKind::Storage, "disk full");
+        assert_eq!(log.filtered(Severity::Warn).count(), 2);
+        assert_eq!(log.count_since(Severity::Error, start), 1);
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::VenueConfig;
+use crate::data::MarketSnapshot;
+use crate::history::SnapshotHistory;
+
+/// Per-interval funding formula parameters, in bps of notional.
+///
+/// With a dampener the venue pays `max(d, P) + min(-d, P)` (Deribit style);
+/// otherwise `P + clamp(I - P, -c, c)` (Binance/BitMEX style). The result is
+/// capped at `±cap_bps` when a cap is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FundingModel {
+    pub interest_rate_bps: f64,
+    pub clamp_bps: f64,
+    pub dampener_bps: f64,
+    pub cap_bps: f64,
+}
+
+impl FundingModel {
+    pub fn apply(&self, premium: f64) -> f64 {
+        let rate = if self.dampener_bps > 0.0 {
+            let dampener = self.dampener_bps / 10_000.0;
+            premium.max(dampener) + premium.min(-dampener)
+        } else {
+            let interest = self.interest_rate_bps / 10_000.0;
+            let clamp = self.clamp_bps / 10_000.0;
+            premium + (interest - premium).clamp(-clamp, clamp)
+        };
+
+        if self.cap_bps > 0.0 {
+            let cap = self.cap_bps / 10_000.0;
+            rate.clamp(-cap, cap)
+        } else {
+            rate
+        }
+    }
+}
+
+/// Model estimate for the next settlement alongside the venue's own number.
+#[derive(Debug, Clone)]
+pub struct FundingForecast {
+    pub venue: String,
+    pub symbol: String,
+    pub estimate: f64,
+    pub venue_prediction: Option<f64>,
+    pub settlement: Option<DateTime<Utc>>,
+    /// Mean absolute error of past estimates against realized funding.
+    pub mean_abs_error: Option<f64>,
+    pub scored_settlements: usize,
+}
+
+#[derive(Debug, Default)]
+struct ForecastTrack {
+    pending: Option<(DateTime<Utc>, f64)>,
+    abs_error_sum: f64,
+    scored: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct FundingForecaster {
+    tracks: HashMap<String, ForecastTrack>,
+}
+
+impl FundingForecaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Estimates the next settlement for every perp and scores the previous
+    /// estimate once its settlement time has passed.
+    pub fn update(
+        &mut self,
+        snapshots: &[MarketSnapshot],
+        history: &SnapshotHistory,
+        venues: &[VenueConfig],
+    ) -> Vec<FundingForecast> {
+        self.update_at(snapshots, history, venues, Utc::now())
+    }
+
+    fn update_at(
+        &mut self,
+        snapshots: &[MarketSnapshot],
+        history: &SnapshotHistory,
+        venues: &[VenueConfig],
+        now: DateTime<Utc>,
+    ) -> Vec<FundingForecast> {
+        snapshots
+            .iter()
+            .filter(|snapshot| snapshot.is_perp())
+            .filter_map(|snapshot| {
+                let venue = venues.iter().find(|venue| venue.name == snapshot.venue)?;
+                let interval = Duration::hours(venue.funding_interval_hours.max(1) as i64);
+                let premium = interval_premium(snapshot, history, interval)?;
+                let estimate = venue.funding_model.apply(premium);
+
+                let track = self
+                    .tracks
+                    .entry(format!("{}::{}", snapshot.venue, snapshot.symbol))
+                    .or_default();
+                if let Some((settlement, forecast)) = track.pending
+                    && settlement <= now
+                    && snapshot.last_updated >= settlement
+                {
+                    track.abs_error_sum += (snapshot.funding_rate - forecast).abs();
+                    track.scored += 1;
+                    track.pending = None;
+                }
+                if let Some(settlement) = snapshot.next_funding_time.filter(|at| *at > now) {
+                    track.pending = Some((settlement, estimate));
+                }
+
+                Some(FundingForecast {
+                    venue: snapshot.venue.clone(),
+                    symbol: snapshot.symbol.clone(),
+                    estimate,
+                    venue_prediction: snapshot.predicted_funding_rate,
+                    settlement: snapshot.next_funding_time,
+                    mean_abs_error: (track.scored > 0)
+                        .then(|| track.abs_error_sum / track.scored as f64),
+                    scored_settlements: track.scored,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Average premium of the perp over its index since the current funding
+/// interval opened, including the latest snapshot.
+fn interval_premium(
+    snapshot: &MarketSnapshot,
+    history: &SnapshotHistory,
+    interval: Duration,
+) -> Option<f64> {
+    let current = premium(snapshot.perp_price?, snapshot.reference_price())?;
+    let opened = snapshot
+        .next_funding_time
+        .map(|next| next - interval)
+        .unwrap_or_else(|| Utc::now() - interval);
+
+    let mut premia: Vec<f64> = history
+        .series(&snapshot.venue, &snapshot.symbol)
+        .into_iter()
+        .flatten()
+        // The snapshot may already be recorded; count it once, as `current`.
+        .filter(|sample| sample.at >= opened && sample.at < snapshot.last_updated)
+        .filter_map(|sample| premium(sample.price, sample.reference_price))
+        .collect();
+    premia.push(current);
+    Some(premia.iter().sum::<f64>() / premia.len() as f64)
+}
+
+fn premium(price: f64, reference: f64) -> Option<f64> {
+    (reference > 0.0).then(|| price / reference - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn model(interest: f64, clamp: f64, dampener: f64, cap: f64) -> FundingModel {
+        FundingModel {
+            interest_rate_bps: interest,
+            clamp_bps: clamp,
+            dampener_bps: dampener,
+            cap_bps: cap,
+        }
+    }
+
+    fn bps(rate: f64) -> f64 {
+        (rate * 10_000.0 * 1e6).round() / 1e6
+    }
+
+    fn perp(price: f64, at: DateTime<Utc>, next: DateTime<Utc>) -> MarketSnapshot {
+        let mut snapshot = MarketSnapshot::placeholder(
+            "Binance",
+            "Perp",
+            "BTCUSDT",
+            100.0,
+            Some(price),
+            0.0,
+            None,
+            Some(next),
+        );
+        snapshot.index_price = Some(100.0);
+        snapshot.last_updated = at;
+        snapshot
+    }
+
+    #[test]
+    fn clamp_pulls_small_premia_to_the_interest_rate() {
+        let binance = model(1.0, 5.0, 0.0, 0.0);
+        assert_eq!(bps(binance.apply(0.0003)), 1.0);
+        assert_eq!(bps(binance.apply(0.0010)), 5.0);
+        assert_eq!(bps(binance.apply(-0.0010)), -5.0);
+    }
+
+    #[test]
+    fn dampener_zeroes_premia_inside_the_band() {
+        let deribit = model(0.0, 0.0, 5.0, 0.0);
+        assert_eq!(bps(deribit.apply(0.0003)), 0.0);
+        assert_eq!(bps(deribit.apply(-0.0004)), 0.0);
+        assert_eq!(bps(deribit.apply(0.0008)), 3.0);
+        assert_eq!(bps(deribit.apply(-0.0008)), -3.0);
+    }
+
+    #[test]
+    fn cap_bounds_the_rate_either_way() {
+        let capped = model(0.0, 0.0, 5.0, 50.0);
+        assert_eq!(bps(capped.apply(0.02)), 50.0);
+        assert_eq!(bps(capped.apply(-0.02)), -50.0);
+        assert_eq!(bps(model(1.0, 5.0, 0.0, 0.0).apply(0.02)), 195.0);
+    }
+
+    #[test]
+    fn interval_premium_counts_the_current_snapshot_once() {
+        let now = Utc::now();
+        let next = now + Duration::hours(4);
+        let mut history = SnapshotHistory::new();
+        history.record(&[perp(100.10, now - Duration::hours(2), next)]);
+        let current = perp(100.30, now, next);
+        history.record(std::slice::from_ref(&current));
+
+        let premium = interval_premium(&current, &history, Duration::hours(8)).unwrap();
+        assert_eq!(bps(premium), 20.0);
+    }
+
+    #[test]
+    fn samples_before_the_interval_opened_are_ignored() {
+        let now = Utc::now();
+        let next = now + Duration::hours(4);
+        let mut history = SnapshotHistory::new();
+        history.record(&[perp(101.0, now - Duration::hours(5), next)]);
+        let current = perp(100.10, now, next);
+        let premium = interval_premium(&current, &history, Duration::hours(8)).unwrap();
+        assert_eq!(bps(premium), 10.0);
+    }
+
+    #[test]
+    fn estimates_are_scored_once_their_settlement_passes() {
+        let venues = AppConfig::default().venues;
+        let history = SnapshotHistory::new();
+        let mut forecaster = FundingForecaster::new();
+        let now = Utc::now();
+        let settlement = now + Duration::hours(1);
+
+        let before = perp(100.03, now, settlement);
+        let forecast = forecaster.update_at(&[before], &history, &venues, now);
+        assert_eq!(forecast[0].mean_abs_error, None);
+        let estimate = forecast[0].estimate;
+
+        let mut settled = perp(100.03, settlement, settlement + Duration::hours(8));
+        settled.funding_rate = estimate + 0.0002;
+        let later = settlement + Duration::minutes(1);
+        let forecast = forecaster.update_at(&[settled.clone()], &history, &venues, later);
+        assert_eq!(forecast[0].scored_settlements, 1);
+        assert_eq!(bps(forecast[0].mean_abs_error.unwrap()), 2.0);
+
+        // The same settlement is not scored twice.
+        let forecast = forecaster.update_at(&[settled], &history, &venues, later);
+        assert_eq!(forecast[0].scored_settlements, 1);
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Consecutive venue failures that trip the circuit open.
+const OPEN_AFTER_FAILURES: u32 = 5;
+const BASE_BACKOFF_MS: i64 = 1_000;
+const MAX_BACKOFF_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VenueState {
+    Healthy,
+    /// Recent failures, still sending requests.
+    Degraded,
+    /// Tripped; no requests until the cool-down passes.
+    Open,
+    /// Cool-down over; one probe request decides open vs healthy.
+    HalfOpen,
+}
+
+impl fmt::Display for VenueState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Healthy => "ok",
+            Self::Degraded => "degraded",
+            Self::Open => "open",
+            Self::HalfOpen => "probing",
+        })
+    }
+}
+
+/// Exponential backoff with jitter: `base * 2^attempt`, capped, scaled by a
+/// random factor in [0.5, 1.0) so venues and symbols do not retry in step.
+pub fn backoff(attempt: u32) -> Duration {
+    let exp = BASE_BACKOFF_MS.saturating_mul(1_i64 << attempt.min(20));
+    let capped = exp.min(MAX_BACKOFF_SECS * 1_000);
+    Duration::milliseconds((capped as f64 * (0.5 + jitter() / 2.0)) as i64)
+}
+
+/// Cheap [0, 1) jitter from the clock; good enough to spread retries.
+fn jitter() -> f64 {
+    let nanos = Utc::now().timestamp_subsec_nanos() as u64;
+    (nanos.wrapping_mul(6_364_136_223_846_793_005) >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+/// Per-venue circuit breaker: healthy → degraded → open → half-open.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    pub state: VenueState,
+    pub consecutive_failures: u32,
+    /// Times the circuit has re-opened without recovering; drives the
+    /// cool-down length.
+    trips: u32,
+    pub retry_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            state: VenueState::Healthy,
+            consecutive_failures: 0,
+            trips: 0,
+            retry_at: None,
+            last_error: None,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Whether a request may go out now; an expired open circuit moves to
+    /// half-open and lets exactly one probe through.
+    pub fn allow(&mut self, now: DateTime<Utc>) -> bool {
+        match self.state {
+            VenueState::Healthy | VenueState::Degraded => true,
+            VenueState::HalfOpen => false,
+            VenueState::Open => {
+                if self.retry_at.is_none_or(|at| now >= at) {
+                    self.state = VenueState::HalfOpen;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// A request got through. The venue only recovers on a successful
+    /// probe or once none of its feeds are failing; otherwise the streak is
+    /// cleared, since the venue answered, but it stays degraded.
+    pub fn record_success(&mut self, feeds_healthy: bool) {
+        if feeds_healthy || self.state == VenueState::HalfOpen {
+            *self = Self::default();
+        } else {
+            self.consecutive_failures = 0;
+        }
+    }
+
+    /// The probe never reached the venue (e.g. local rate budget); probe
+    /// again on the next request.
+    fn cancel_probe(&mut self, now: DateTime<Utc>) {
+        if self.state == VenueState::HalfOpen {
+            self.state = VenueState::Open;
+            self.retry_at = Some(now);
+        }
+    }
+
+    pub fn record_failure(&mut self, error: String, now: DateTime<Utc>) {
+        self.consecutive_failures += 1;
+        self.last_error = Some(error);
+        if self.state == VenueState::HalfOpen || self.consecutive_failures >= OPEN_AFTER_FAILURES {
+            self.state = VenueState::Open;
+            self.retry_at = Some(now + backoff(self.trips + 2));
+            self.trips += 1;
+        } else {
+            self.state = VenueState::Degraded;
+        }
+    }
+}
+
+/// Retry schedule for one feed (venue + symbol).
+#[derive(Debug, Clone, Default)]
+struct RetrySchedule {
+    attempts: u32,
+    retry_at: Option<DateTime<Utc>>,
+}
+
+/// Where the data a feed last served came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedSource {
+    /// Polled over REST within the cache TTL.
+    Rest,
+    /// The fetch failed or was held back; last good data is shown.
+    StaleCache,
+}
+
+impl fmt::Display for FeedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Rest => "REST",
+            Self::StaleCache => "stale cache",
+        })
+    }
+}
+
+/// Request outcomes kept per feed for latency and error-rate figures.
+const STATS_WINDOW: usize = 100;
+
+/// Request history of one venue/symbol feed.
+#[derive(Debug, Clone)]
+pub struct FeedStats {
+    pub venue: String,
+    /// Symbol, or e.g. "BTC options" for chain and listing requests.
+    pub subject: String,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<(DateTime<Utc>, String)>,
+    pub source: Option<FeedSource>,
+    /// When the data currently served was fetched.
+    pub data_at: Option<DateTime<Utc>>,
+    latencies_ms: VecDeque<f64>,
+    /// `true` for each failed request, oldest first.
+    failures: VecDeque<bool>,
+}
+
+impl FeedStats {
+    fn new(venue: &str, subject: &str) -> Self {
+        Self {
+            venue: venue.to_string(),
+            subject: subject.to_string(),
+            last_success: None,
+            last_error: None,
+            source: None,
+            data_at: None,
+            latencies_ms: VecDeque::new(),
+            failures: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, latency: StdDuration, failed: bool) {
+        push_bounded(&mut self.latencies_ms, latency.as_secs_f64() * 1_000.0);
+        push_bounded(&mut self.failures, failed);
+    }
+
+    /// Latency quantile in milliseconds over the recent requests.
+    pub fn latency_ms(&self, quantile: f64) -> Option<f64> {
+        if self.latencies_ms.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.latencies_ms.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let rank = ((sorted.len() - 1) as f64 * quantile.clamp(0.0, 1.0)).round() as usize;
+        Some(sorted[rank])
+    }
+
+    /// Share of recent requests that failed.
+    pub fn error_rate(&self) -> Option<f64> {
+        if self.failures.is_empty() {
+            return None;
+        }
+        let failed = self.failures.iter().filter(|failed| **failed).count();
+        Some(failed as f64 / self.failures.len() as f64)
+    }
+
+    pub fn cache_age(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.data_at.map(|at| now - at)
+    }
+}
+
+fn push_bounded<T>(values: &mut VecDeque<T>, value: T) {
+    if values.len() == STATS_WINDOW {
+        values.pop_front();
+    }
+    values.push_back(value);
+}
+
+/// Breakers per venue, plus jittered retry backoff and request statistics
+/// per feed.
+#[derive(Debug, Default)]
+pub struct FeedHealth {
+    venues: HashMap<String, CircuitBreaker>,
+    retries: HashMap<String, RetrySchedule>,
+    feeds: HashMap<String, FeedStats>,
+}
+
+impl FeedHealth {
+    /// `Err` carries why the request is being held back.
+    pub fn check(&mut self, venue: &str, subject: &str, now: DateTime<Utc>) -> Result<(), String> {
+        if let Some(at) = self
+            .retries
+            .get(&feed_key(venue, subject))
+            .and_then(|retry| retry.retry_at)
+            && now < at
+        {
+            return Err(format!("backing off until {}", at.format("%H:%M:%S")));
+        }
+        let breaker = self.venues.entry(venue.to_string()).or_default();
+        if breaker.allow(now) {
+            Ok(())
+        } else {
+            Err(match breaker.retry_at {
+                Some(at) => format!(
+                    "{} circuit {} until {}",
+                    venue,
+                    breaker.state,
+                    at.format("%H:%M:%S")
+                ),
+                None => format!("{} circuit {}", venue, breaker.state),
+            })
+        }
+    }
+
+    pub fn record_success(
+        &mut self,
+        venue: &str,
+        subject: &str,
+        latency: StdDuration,
+        now: DateTime<Utc>,
+    ) {
+        let key = feed_key(venue, subject);
+        self.retries.remove(&key);
+        let feed = self
+            .feeds
+            .entry(key)
+            .or_insert_with(|| FeedStats::new(venue, subject));
+        feed.record(latency, false);
+        feed.last_success = Some(now);
+        let prefix = feed_key(venue, "");
+        let feeds_healthy = !self.retries.keys().any(|key| key.starts_with(&prefix));
+        self.venues
+            .entry(venue.to_string())
+            .or_default()
+            .record_success(feeds_healthy);
+    }
+
+    pub fn record_failure(
+        &mut self,
+        venue: &str,
+        subject: &str,
+        error: String,
+        latency: StdDuration,
+        now: DateTime<Utc>,
+    ) {
+        let key = feed_key(venue, subject);
+        let retry = self.retries.entry(key.clone()).or_default();
+        retry.retry_at = Some(now + backoff(retry.attempts));
+        retry.attempts += 1;
+        let feed = self
+            .feeds
+            .entry(key)
+            .or_insert_with(|| FeedStats::new(venue, subject));
+        feed.record(latency, true);
+        feed.last_error = Some((now, error.clone()));
+        self.venues
+            .entry(venue.to_string())
+            .or_default()
+            .record_failure(error, now);
+    }
+
+    /// The request was held back before reaching the venue.
+    pub fn record_skipped(&mut self, venue: &str, now: DateTime<Utc>) {
+        if let Some(breaker) = self.venues.get_mut(venue) {
+            breaker.cancel_probe(now);
+        }
+    }
+
+    /// Notes what a feed handed to the UI this tick and how old it is.
+    pub fn record_served(
+        &mut self,
+        venue: &str,
+        subject: &str,
+        source: FeedSource,
+        data_at: DateTime<Utc>,
+    ) {
+        let feed = self
+            .feeds
+            .entry(feed_key(venue, subject))
+            .or_insert_with(|| FeedStats::new(venue, subject));
+        feed.source = Some(source);
+        feed.data_at = Some(data_at);
+    }
+
+    /// Why requests to `venue` would be held back right now: an open
+    /// circuit, or feeds still backing off after failures.
+    pub fn held_back(&self, venue: &str, now: DateTime<Utc>) -> Option<String> {
+        if let Some(breaker) = self.venues.get(venue)
+            && breaker.state == VenueState::Open
+            && let Some(at) = breaker.retry_at.filter(|at| now < *at)
+        {
+            return Some(format!("circuit open until {}", at.format("%H:%M:%S")));
+        }
+        let prefix = feed_key(venue, "");
+        let backing_off = self
+            .retries
+            .iter()
+            .filter(|(key, retry)| {
+                key.starts_with(&prefix) && retry.retry_at.is_some_and(|at| now < at)
+            })
+            .count();
+        (backing_off > 0).then(|| format!("{} feeds backing off", backing_off))
+    }
+
+    /// Breaker per venue, sorted by venue name.
+    pub fn venues(&self) -> Vec<(String, CircuitBreaker)> {
+        let mut venues: Vec<(String, CircuitBreaker)> = self
+            .venues
+            .iter()
+            .map(|(venue, breaker)| (venue.clone(), breaker.clone()))
+            .collect();
+        venues.sort_by(|a, b| a.0.cmp(&b.0));
+        venues
+    }
+
+    /// Statistics per feed, sorted by venue then subject.
+    pub fn feeds(&self) -> Vec<FeedStats> {
+        let mut feeds: Vec<FeedStats> = self.feeds.values().cloned().collect();
+        feeds.sort_by(|a, b| (&a.venue, &a.subject).cmp(&(&b.venue, &b.subject)));
+        feeds
+    }
+}
+
+fn feed_key(venue: &str, subject: &str) -> String {
+    format!("{}::{}", venue, subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::seconds(secs)
+    }
+
+    const LATENCY: StdDuration = StdDuration::from_millis(50);
+
+    fn state(health: &FeedHealth, venue: &str) -> VenueState {
+        health
+            .venues()
+            .into_iter()
+            .find(|(name, _)| name == venue)
+            .map_or(VenueState::Healthy, |(_, breaker)| breaker.state)
+    }
+
+    #[test]
+    fn breaker_opens_after_consecutive_failures() {
+        let mut breaker = CircuitBreaker::default();
+        for failure in 1..OPEN_AFTER_FAILURES {
+            breaker.record_failure("timeout".into(), at(0));
+            assert_eq!(breaker.state, VenueState::Degraded, "failure {failure}");
+        }
+        breaker.record_failure("timeout".into(), at(0));
+        assert_eq!(breaker.state, VenueState::Open);
+        assert_eq!(breaker.last_error.as_deref(), Some("timeout"));
+
+        let retry_at = breaker.retry_at.unwrap();
+        assert!(retry_at > at(0));
+        assert!(!breaker.allow(retry_at - Duration::milliseconds(1)));
+    }
+
+    #[test]
+    fn half_open_probe_decides_the_circuit() {
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..OPEN_AFTER_FAILURES {
+            breaker.record_failure("timeout".into(), at(0));
+        }
+        let retry_at = breaker.retry_at.unwrap();
+        assert!(breaker.allow(retry_at));
+        assert_eq!(breaker.state, VenueState::HalfOpen);
+        assert!(!breaker.allow(retry_at), "only one probe at a time");
+
+        breaker.record_failure("still down".into(), retry_at);
+        assert_eq!(breaker.state, VenueState::Open);
+        assert!(breaker.retry_at.unwrap() > retry_at);
+
+        let retry_at = breaker.retry_at.unwrap();
+        assert!(breaker.allow(retry_at));
+        breaker.record_success(false);
+        assert_eq!(breaker.state, VenueState::Healthy);
+        assert_eq!(breaker.consecutive_failures, 0);
+        assert!(breaker.retry_at.is_none());
+    }
+
+    #[test]
+    fn skipped_probe_is_retried_immediately() {
+        let mut health = FeedHealth::default();
+        for secs in 0..OPEN_AFTER_FAILURES as i64 {
+            health.record_failure("OKX", "BTC", "timeout".into(), LATENCY, at(secs));
+        }
+        let (_, breaker) = health.venues().remove(0);
+        let retry_at = breaker.retry_at.unwrap();
+        health.retries.clear();
+
+        assert!(health.check("OKX", "BTC", retry_at).is_ok());
+        assert_eq!(state(&health, "OKX"), VenueState::HalfOpen);
+        health.record_skipped("OKX", retry_at);
+        assert_eq!(state(&health, "OKX"), VenueState::Open);
+        assert!(health.check("OKX", "BTC", retry_at).is_ok());
+    }
+
+    #[test]
+    fn one_failing_feed_keeps_the_venue_degraded_without_flapping() {
+        let mut health = FeedHealth::default();
+        health.record_failure("Binance", "DOGEUSDT", "bad symbol".into(), LATENCY, at(0));
+        assert_eq!(state(&health, "Binance"), VenueState::Degraded);
+
+        for cycle in 1..=10 {
+            health.record_success("Binance", "BTCUSDT", LATENCY, at(cycle * 30));
+            assert_eq!(
+                state(&health, "Binance"),
+                VenueState::Degraded,
+                "cycle {cycle}"
+            );
+            health.record_failure(
+                "Binance",
+                "DOGEUSDT",
+                "bad symbol".into(),
+                LATENCY,
+                at(cycle * 30 + 1),
+            );
+            assert_eq!(
+                state(&health, "Binance"),
+                VenueState::Degraded,
+                "cycle {cycle}"
+            );
+        }
+
+        health.record_success("Binance", "DOGEUSDT", LATENCY, at(400));
+        assert_eq!(state(&health, "Binance"), VenueState::Healthy);
+        assert!(health.held_back("Binance", at(400)).is_none());
+    }
+
+    #[test]
+    fn failing_feed_backs_off_on_its_own() {
+        let mut health = FeedHealth::default();
+        health.record_failure("Bybit", "ETHUSDT", "timeout".into(), LATENCY, at(0));
+        let held = health.check("Bybit", "ETHUSDT", at(0)).unwrap_err();
+        assert!(held.starts_with("backing off until"), "{held}");
+        assert!(health.check("Bybit", "BTCUSDT", at(0)).is_ok());
+        assert_eq!(
+            health.held_back("Bybit", at(0)).as_deref(),
+            Some("1 feeds backing off")
+        );
+        assert!(
+            health
+                .check("Bybit", "ETHUSDT", at(MAX_BACKOFF_SECS))
+                .is_ok()
+        );
+    }
+}
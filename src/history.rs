@@ -0,0 +1,236 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::data::MarketSnapshot;
+use crate::persist;
+
+/// Longest window any consumer asks for; older samples are pruned.
+const RETENTION_DAYS: i64 = 30;
+/// Cached snapshots repeat every tick, so only keep one sample per interval.
+const MIN_SAMPLE_SPACING_SECS: i64 = 30;
+/// Samples older than a day are thinned to this spacing before persisting.
+pub const COMPACT_SPACING_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HistorySample {
+    pub at: DateTime<Utc>,
+    pub funding_rate: f64,
+    pub price: f64,
+    pub reference_price: f64,
+}
+
+/// Per-instrument time series built from the snapshots seen by the app.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotHistory {
+    series: HashMap<String, VecDeque<HistorySample>>,
+}
+
+impl SnapshotHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a previously persisted history; a missing file is an empty one.
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(persist::load_json(path, "history")?.unwrap_or_default())
+    }
+
+    /// Thins old samples and writes the history atomically to `path`.
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        self.compact(Utc::now());
+        persist::save_json(path, self, "history")
+    }
+
+    fn compact(&mut self, now: DateTime<Utc>) {
+        let recent = now - Duration::days(1);
+        for series in self.series.values_mut() {
+            let mut last_kept: Option<DateTime<Utc>> = None;
+            series.retain(|sample| {
+                let keep = sample.at >= recent
+                    || last_kept.is_none_or(|kept| {
+                        (sample.at - kept).num_seconds() >= COMPACT_SPACING_SECS
+                    });
+                if keep {
+                    last_kept = Some(sample.at);
+                }
+                keep
+            });
+        }
+    }
+
+    pub fn record(&mut self, snapshots: &[MarketSnapshot]) {
+        self.record_at(snapshots, Utc::now());
+    }
+
+    fn record_at(&mut self, snapshots: &[MarketSnapshot], now: DateTime<Utc>) {
+        let cutoff = now - Duration::days(RETENTION_DAYS);
+        for snapshot in snapshots {
+            let series = self
+                .series
+                .entry(series_key(&snapshot.venue, &snapshot.symbol))
+                .or_default();
+
+            let at = snapshot.last_updated;
+            let is_new = series
+                .back()
+                .is_none_or(|last| (at - last.at).num_seconds() >= MIN_SAMPLE_SPACING_SECS);
+            if is_new {
+                series.push_back(HistorySample {
+                    at,
+                    funding_rate: snapshot.funding_rate,
+                    price: snapshot.perp_price.unwrap_or(snapshot.spot_price),
+                    reference_price: snapshot.reference_price(),
+                });
+            }
+
+            while series.front().is_some_and(|sample| sample.at < cutoff) {
+                series.pop_front();
+            }
+        }
+    }
+
+    pub fn series(&self, venue: &str, symbol: &str) -> Option<&VecDeque<HistorySample>> {
+        self.series.get(&series_key(venue, symbol))
+    }
+
+    /// Samples newer than `window` ago, oldest first.
+    pub fn window(
+        &self,
+        venue: &str,
+        symbol: &str,
+        window: Duration,
+    ) -> impl Iterator<Item = &HistorySample> {
+        self.window_until(venue, symbol, window, Utc::now())
+    }
+
+    /// Samples in the `window` ending at `end`, oldest first.
+    pub fn window_until(
+        &self,
+        venue: &str,
+        symbol: &str,
+        window: Duration,
+        end: DateTime<Utc>,
+    ) -> impl Iterator<Item = &HistorySample> {
+        let cutoff = end - window;
+        self.series(venue, symbol)
+            .into_iter()
+            .flatten()
+            .filter(move |sample| sample.at >= cutoff && sample.at <= end)
+    }
+}
+
+fn series_key(venue: &str, symbol: &str) -> String {
+    format!("{}::{}", venue, symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::seconds(secs)
+    }
+
+    fn snapshot(secs: i64, price: f64) -> MarketSnapshot {
+        let mut snapshot = MarketSnapshot::placeholder(
+            "Binance",
+            "Perp",
+            "BTCUSDT",
+            100.0,
+            Some(price),
+            0.0001,
+            None,
+            None,
+        );
+        snapshot.index_price = Some(99.0);
+        snapshot.last_updated = at(secs);
+        snapshot
+    }
+
+    fn times(history: &SnapshotHistory) -> Vec<i64> {
+        history
+            .series("Binance", "BTCUSDT")
+            .into_iter()
+            .flatten()
+            .map(|sample| (sample.at - at(0)).num_seconds())
+            .collect()
+    }
+
+    #[test]
+    fn samples_closer_than_the_spacing_are_skipped() {
+        let mut history = SnapshotHistory::new();
+        for secs in [0, 10, 29, 30, 75] {
+            history.record_at(&[snapshot(secs, 100.0)], at(secs));
+        }
+        assert_eq!(times(&history), [0, 30, 75]);
+        let sample = history.series("Binance", "BTCUSDT").unwrap()[0];
+        assert_eq!(sample.price, 100.0);
+        assert_eq!(sample.reference_price, 99.0);
+    }
+
+    #[test]
+    fn samples_past_retention_are_pruned() {
+        let mut history = SnapshotHistory::new();
+        let day = 86_400;
+        history.record_at(&[snapshot(0, 100.0)], at(0));
+        history.record_at(&[snapshot(day, 100.0)], at(day));
+        history.record_at(&[snapshot(31 * day, 100.0)], at(31 * day));
+        assert_eq!(times(&history), [day, 31 * day]);
+    }
+
+    #[test]
+    fn compaction_thins_only_samples_older_than_a_day() {
+        let mut history = SnapshotHistory::new();
+        let day = 86_400;
+        let old: Vec<i64> = (0..20).map(|step| step * 60).collect();
+        let recent: Vec<i64> = (0..5).map(|step| 2 * day + step * 30).collect();
+        for secs in old.iter().chain(&recent) {
+            history.record_at(&[snapshot(*secs, 100.0)], at(*secs));
+        }
+        history.compact(at(2 * day + 600));
+        let mut expected = vec![0, 300, 600, 900];
+        expected.extend(&recent);
+        assert_eq!(times(&history), expected);
+    }
+
+    #[test]
+    fn window_ends_where_asked() {
+        let mut history = SnapshotHistory::new();
+        for secs in [0, 60, 120, 180] {
+            history.record_at(&[snapshot(secs, 100.0)], at(secs));
+        }
+        let window: Vec<DateTime<Utc>> = history
+            .window_until("Binance", "BTCUSDT", Duration::seconds(90), at(150))
+            .map(|sample| sample.at)
+            .collect();
+        assert_eq!(window, [at(60), at(120)]);
+    }
+
+    #[test]
+    fn history_survives_a_save_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("quantumdesk-history-{}.json", std::process::id()));
+        let mut history = SnapshotHistory::new();
+        for secs in [0, 300, 600] {
+            history.record_at(&[snapshot(secs, 100.0 + secs as f64)], at(secs));
+        }
+        history.save(&path).unwrap();
+        let loaded = SnapshotHistory::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(times(&loaded), [0, 300, 600]);
+        assert_eq!(loaded.series("Binance", "BTCUSDT").unwrap()[2].price, 700.0);
+        assert!(
+            SnapshotHistory::load(&path)
+                .unwrap()
+                .series("Binance", "BTCUSDT")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Key bindings: named actions, the default and vim presets, and overrides
+//! from config.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// Number of workspaces reachable with a direct binding.
+const WORKSPACE_KEYS: usize = 9;
+
+/// Something a key can do outside text inputs (palette, picker, filter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Action {
+    Quit,
+    /// Closes the detail view or help; quits from the main screen.
+    Back,
+    Help,
+    Palette,
+    AddInstrument,
+    ToggleHealth,
+    StatsWindow,
+    FocusNext,
+    /// Freezes or resumes the market panes.
+    Pause,
+    /// Re-fetches every venue, skipping the cache.
+    Refresh,
+    /// Re-fetches the selected row's venue, skipping the cache.
+    RefreshVenue,
+    /// Zero-based workspace index; named `workspace_1`.. in config.
+    Workspace(usize),
+    Down,
+    Up,
+    Left,
+    Right,
+    /// Detail view for the selected row or heatmap cell.
+    Open,
+    SortNext,
+    SortReverse,
+    Filter,
+    HeatmapMetric,
+    /// Minimum severity shown in the log pane.
+    LogSeverity,
+}
+
+/// Where an action applies, for grouping the help overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyContext {
+    General,
+    Navigation,
+    Table,
+    Heatmap,
+    Log,
+}
+
+impl fmt::Display for KeyContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::General => "General",
+            Self::Navigation => "Navigation (focused pane)",
+            Self::Table => "Funding Monitor",
+            Self::Heatmap => "Heatmap",
+            Self::Log => "Log",
+        })
+    }
+}
+
+impl Action {
+    fn all() -> Vec<Action> {
+        let mut actions = vec![
+            Self::Quit,
+            Self::Back,
+            Self::Help,
+            Self::Palette,
+            Self::AddInstrument,
+            Self::ToggleHealth,
+            Self::StatsWindow,
+            Self::FocusNext,
+            Self::Pause,
+            Self::Refresh,
+            Self::RefreshVenue,
+        ];
+        actions.extend((0..WORKSPACE_KEYS).map(Self::Workspace));
+        actions.extend([
+            Self::Down,
+            Self::Up,
+            Self::Left,
+            Self::Right,
+            Self::Open,
+            Self::SortNext,
+            Self::SortReverse,
+            Self::Filter,
+            Self::HeatmapMetric,
+            Self::LogSeverity,
+        ]);
+        actions
+    }
+
+    pub fn context(self) -> KeyContext {
+        match self {
+            Self::Down | Self::Up | Self::Left | Self::Right | Self::Open => KeyContext::Navigation,
+            Self::SortNext | Self::SortReverse | Self::Filter => KeyContext::Table,
+            Self::HeatmapMetric => KeyContext::Heatmap,
+            Self::LogSeverity => KeyContext::Log,
+            _ => KeyContext::General,
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::Quit => "Quit".into(),
+            Self::Back => "Close detail/help, or quit".into(),
+            Self::Help => "Show key bindings".into(),
+            Self::Palette => "Command palette".into(),
+            Self::AddInstrument => "Add instrument".into(),
+            Self::ToggleHealth => "Toggle feed health".into(),
+            Self::StatsWindow => "Cycle funding stats window".into(),
+            Self::FocusNext => "Focus next pane".into(),
+            Self::Pause => "Pause/resume display".into(),
+            Self::Refresh => "Refresh all venues now".into(),
+            Self::RefreshVenue => "Refresh selected row's venue now".into(),
+            Self::Workspace(index) => format!("Workspace {}", index + 1),
+            Self::Down => "Down".into(),
+            Self::Up => "Up".into(),
+            Self::Left => "Left".into(),
+            Self::Right => "Right".into(),
+            Self::Open => "Open detail view".into(),
+            Self::SortNext => "Next sort column".into(),
+            Self::SortReverse => "Reverse sort".into(),
+            Self::Filter => "Filter rows".into(),
+            Self::HeatmapMetric => "Switch funding/basis".into(),
+            Self::LogSeverity => "Cycle severity filter".into(),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|action| action.to_string() == raw.trim())
+            .ok_or_else(|| anyhow!("unknown key action {:?}", raw))
+    }
+}
+
+impl TryFrom<String> for Action {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        raw.parse()
+    }
+}
+
+impl From<Action> for String {
+    fn from(action: Action) -> Self {
+        action.to_string()
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quit => f.write_str("quit"),
+            Self::Back => f.write_str("back"),
+            Self::Help => f.write_str("help"),
+            Self::Palette => f.write_str("palette"),
+            Self::AddInstrument => f.write_str("add_instrument"),
+            Self::ToggleHealth => f.write_str("toggle_health"),
+            Self::StatsWindow => f.write_str("stats_window"),
+            Self::FocusNext => f.write_str("focus_next"),
+            Self::Pause => f.write_str("pause"),
+            Self::Refresh => f.write_str("refresh"),
+            Self::RefreshVenue => f.write_str("refresh_venue"),
+            Self::Workspace(index) => write!(f, "workspace_{}", index + 1),
+            Self::Down => f.write_str("down"),
+            Self::Up => f.write_str("up"),
+            Self::Left => f.write_str("left"),
+            Self::Right => f.write_str("right"),
+            Self::Open => f.write_str("open"),
+            Self::SortNext => f.write_str("sort_next"),
+            Self::SortReverse => f.write_str("sort_reverse"),
+            Self::Filter => f.write_str("filter"),
+            Self::HeatmapMetric => f.write_str("heatmap_metric"),
+            Self::LogSeverity => f.write_str("log_severity"),
+        }
+    }
+}
+
+/// A key with modifiers: `"q"`, `"O"` (or `"shift-o"`), `"ctrl-p"`, `"esc"`, `"enter"`,
+/// `"tab"`, `"up"`, `"space"`, `"f1"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already in the character's case.
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let raw = raw.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = raw;
+        // A lone "-" is the key itself, not a separator.
+        while let Some((prefix, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier {:?} in key {:?}", prefix, raw),
+            };
+            key = rest;
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => {
+                    let number = name
+                        .strip_prefix('f')
+                        .and_then(|number| number.parse().ok())
+                        .with_context(|| format!("unknown key {:?}", raw))?;
+                    KeyCode::F(number)
+                }
+            },
+        };
+        // Terminals report shifted letters by their case, so "shift-o" means
+        // "O"; other characters have no shifted form to match.
+        let code = match code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => {
+                if !ch.is_alphabetic() {
+                    bail!("shift cannot be combined with {:?} in key {:?}", ch, raw);
+                }
+                KeyCode::Char(ch.to_uppercase().next().unwrap_or(ch))
+            }
+            code => code,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        raw.parse()
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(key: KeyBinding) -> Self {
+        key.to_string()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(ch) => write!(f, "{}", ch),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("backtab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::F(number) => write!(f, "f{}", number),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    /// `h`/`l` move left/right, so feed health moves to `H`.
+    Vim,
+}
+
+/// Preset plus per-action overrides; an overridden action gets exactly the
+/// listed keys, which are taken away from any other action.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeymapConfig {
+    pub preset: KeymapPreset,
+    pub bindings: BTreeMap<Action, Vec<KeyBinding>>,
+}
+
+/// An action with the keys bound to it.
+pub type Binding<'a> = (Action, &'a [KeyBinding]);
+
+/// Resolved bindings, in the order the help overlay lists them.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(KeymapPreset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: KeymapPreset) -> Self {
+        let vim = preset == KeymapPreset::Vim;
+        let bindings = Action::all()
+            .into_iter()
+            .map(|action| {
+                let keys: Vec<String> = match action {
+                    Action::Quit => vec!["q".into()],
+                    Action::Back => vec!["esc".into()],
+                    Action::Help => vec!["?".into()],
+                    Action::Palette => vec![":".into(), "ctrl-p".into()],
+                    Action::AddInstrument => vec!["a".into()],
+                    Action::ToggleHealth if vim => vec!["H".into()],
+                    Action::ToggleHealth => vec!["h".into()],
+                    Action::StatsWindow => vec!["s".into()],
+                    Action::FocusNext => vec!["tab".into()],
+                    Action::Pause => vec!["p".into(), "space".into()],
+                    Action::Refresh => vec!["r".into()],
+                    Action::RefreshVenue => vec!["R".into()],
+                    Action::Workspace(index) => vec![(index + 1).to_string()],
+                    Action::Down => vec!["j".into(), "down".into()],
+                    Action::Up => vec!["k".into(), "up".into()],
+                    Action::Left if vim => vec!["h".into(), "left".into()],
+                    Action::Left => vec!["left".into()],
+                    Action::Right if vim => vec!["l".into(), "right".into()],
+                    Action::Right => vec!["right".into()],
+                    Action::Open => vec!["enter".into()],
+                    Action::SortNext => vec!["o".into()],
+                    Action::SortReverse => vec!["O".into()],
+                    Action::Filter => vec!["/".into()],
+                    Action::HeatmapMetric => vec!["m".into()],
+                    Action::LogSeverity => vec!["v".into()],
+                };
+                let keys = keys
+                    .iter()
+                    .map(|key| key.parse().expect("preset key"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    pub fn from_config(config: &KeymapConfig) -> Self {
+        let mut keymap = Self::preset(config.preset);
+        for (action, keys) in &config.bindings {
+            for (_, bound) in &mut keymap.bindings {
+                bound.retain(|key| !keys.contains(key));
+            }
+            if let Some((_, bound)) = keymap
+                .bindings
+                .iter_mut()
+                .find(|(candidate, _)| candidate == action)
+            {
+                *bound = keys.clone();
+            }
+        }
+        keymap
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        let key = KeyBinding::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(candidate, _)| *candidate == action)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+
+    /// First key for `action`, for hints in titles; "-" when unbound.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map_or_else(|| "-".to_string(), |key| key.to_string())
+    }
+
+    /// Bound actions grouped by context, for the help overlay.
+    pub fn grouped(&self) -> Vec<(KeyContext, Vec<Binding<'_>>)> {
+        let mut groups: BTreeMap<KeyContext, Vec<Binding<'_>>> = BTreeMap::new();
+        for (action, keys) in &self.bindings {
+            if !keys.is_empty() {
+                groups
+                    .entry(action.context())
+                    .or_default()
+                    .push((*action, keys.as_slice()));
+            }
+        }
+        groups.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(raw: &str) -> KeyBinding {
+        raw.parse().unwrap()
+    }
+
+    fn config(bindings: &[(Action, &[&str])]) -> KeymapConfig {
+        KeymapConfig {
+            preset: KeymapPreset::Default,
+            bindings: bindings
+                .iter()
+                .map(|(action, keys)| (*action, keys.iter().map(|raw| key(raw)).collect()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn keys_round_trip_through_display() {
+        for raw in [
+            "q",
+            "O",
+            "-",
+            "ctrl-p",
+            "alt-x",
+            "ctrl-alt-k",
+            "esc",
+            "enter",
+            "tab",
+            "backtab",
+            "space",
+            "up",
+            "pagedown",
+            "f1",
+            "f12",
+            "shift-up",
+        ] {
+            assert_eq!(key(raw).to_string(), raw);
+        }
+        assert_eq!(key("Ctrl-Escape").to_string(), "ctrl-esc");
+    }
+
+    #[test]
+    fn actions_round_trip_through_display() {
+        for action in Action::all() {
+            assert_eq!(action.to_string().parse::<Action>().unwrap(), action);
+        }
+        assert!("launch".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn shift_uppercases_letters() {
+        assert_eq!(key("shift-o"), key("O"));
+        assert_eq!(key("ctrl-shift-o"), key("ctrl-O"));
+        assert!("shift-1".parse::<KeyBinding>().is_err());
+        assert!("shift-space".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_rejected() {
+        assert!("meta-q".parse::<KeyBinding>().is_err());
+        assert!("launch".parse::<KeyBinding>().is_err());
+        assert!("".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn shifted_events_match_uppercase_bindings() {
+        let keymap = Keymap::default();
+        let event = KeyEvent::new(KeyCode::Char('O'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&event), Some(Action::SortReverse));
+        let event = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&event), Some(Action::SortNext));
+    }
+
+    #[test]
+    fn overrides_take_their_keys_from_other_actions() {
+        let keymap = Keymap::from_config(&config(&[(Action::Quit, &["ctrl-p", "x"])]));
+        assert_eq!(keymap.keys(Action::Quit), [key("ctrl-p"), key("x")]);
+        assert_eq!(keymap.keys(Action::Palette), [key(":")]);
+    }
+
+    #[test]
+    fn shift_override_leaves_lowercase_binding_alone() {
+        let keymap = Keymap::from_config(&config(&[(Action::Filter, &["shift-o"])]));
+        assert_eq!(keymap.keys(Action::Filter), [key("O")]);
+        assert_eq!(keymap.keys(Action::SortNext), [key("o")]);
+        assert!(keymap.keys(Action::SortReverse).is_empty());
+        assert_eq!(keymap.hint(Action::SortReverse), "-");
+    }
+
+    #[test]
+    fn vim_preset_moves_health_to_capital_h() {
+        let keymap = Keymap::preset(KeymapPreset::Vim);
+        assert_eq!(keymap.keys(Action::ToggleHealth), [key("H")]);
+        assert_eq!(keymap.keys(Action::Left), [key("h"), key("left")]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Workspace layouts: trees of splits and named panes, sized from config.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    Vertical,
+    Horizontal,
+}
+
+/// How much of its split a node takes: `"fill"`, `"auto"` (the pane's
+/// preferred height), a fixed `"12"` rows/columns or `"35%"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PaneSize {
+    #[default]
+    Fill,
+    Auto,
+    Fixed(u16),
+    Percent(u16),
+}
+
+impl FromStr for PaneSize {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let raw = raw.trim();
+        match raw {
+            "fill" => Ok(Self::Fill),
+            "auto" => Ok(Self::Auto),
+            _ => match raw.strip_suffix('%') {
+                Some(percent) => percent
+                    .trim()
+                    .parse()
+                    .map(Self::Percent)
+                    .with_context(|| format!("invalid pane size {:?}", raw)),
+                None => raw.parse().map(Self::Fixed).map_err(|_| {
+                    anyhow!("invalid pane size {:?} (expected fill, auto, N or N%)", raw)
+                }),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for PaneSize {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        raw.parse()
+    }
+}
+
+impl From<PaneSize> for String {
+    fn from(size: PaneSize) -> Self {
+        size.to_string()
+    }
+}
+
+impl fmt::Display for PaneSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fill => f.write_str("fill"),
+            Self::Auto => f.write_str("auto"),
+            Self::Fixed(cells) => write!(f, "{}", cells),
+            Self::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+/// A split of child nodes, or a pane looked up by name in the UI's pane
+/// registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LayoutNode {
+    Split {
+        split: SplitDirection,
+        #[serde(default)]
+        size: PaneSize,
+        panes: Vec<LayoutNode>,
+    },
+    Pane {
+        pane: String,
+        #[serde(default)]
+        size: PaneSize,
+    },
+}
+
+impl LayoutNode {
+    pub fn pane(name: &str, size: PaneSize) -> Self {
+        Self::Pane {
+            pane: name.to_string(),
+            size,
+        }
+    }
+
+    pub fn split(split: SplitDirection, size: PaneSize, panes: Vec<LayoutNode>) -> Self {
+        Self::Split { split, size, panes }
+    }
+
+    /// Names of every pane in the subtree, in layout order.
+    pub fn pane_names(&self) -> Vec<&str> {
+        match self {
+            Self::Pane { pane, .. } => vec![pane.as_str()],
+            Self::Split { panes, .. } => panes.iter().flat_map(Self::pane_names).collect(),
+        }
+    }
+
+    fn size(&self) -> PaneSize {
+        match self {
+            Self::Split { size, .. } | Self::Pane { size, .. } => *size,
+        }
+    }
+
+    /// Whether the subtree has a pane to show; `height` returning `Some(0)`
+    /// hides a pane.
+    fn is_visible(&self, height: &dyn Fn(&str) -> Option<u16>) -> bool {
+        match self {
+            Self::Pane { pane, .. } => height(pane) != Some(0),
+            Self::Split { panes, .. } => panes.iter().any(|node| node.is_visible(height)),
+        }
+    }
+
+    /// Rows the subtree wants when sized `auto`; `None` when any part fills.
+    fn preferred_height(&self, height: &dyn Fn(&str) -> Option<u16>) -> Option<u16> {
+        match self {
+            Self::Pane { pane, .. } => height(pane),
+            Self::Split { split, panes, .. } => {
+                let heights = panes
+                    .iter()
+                    .filter(|node| node.is_visible(height))
+                    .map(|node| node.preferred_height(height));
+                match split {
+                    SplitDirection::Horizontal => heights.max().flatten(),
+                    SplitDirection::Vertical => heights.sum(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    pub name: String,
+    pub layout: LayoutNode,
+}
+
+/// Resolves `node` inside `area` to one rect per visible pane, in layout
+/// order. `height` gives each pane's preferred height (`None` to fill,
+/// `Some(0)` to hide).
+pub fn place(
+    node: &LayoutNode,
+    area: Rect,
+    height: &dyn Fn(&str) -> Option<u16>,
+) -> Vec<(String, Rect)> {
+    match node {
+        LayoutNode::Pane { pane, .. } => vec![(pane.clone(), area)],
+        LayoutNode::Split { split, panes, .. } => {
+            let children: Vec<&LayoutNode> = panes
+                .iter()
+                .filter(|child| child.is_visible(height))
+                .collect();
+            // A lone survivor takes the whole split rather than its share.
+            let lone = children.len() == 1;
+            let constraints: Vec<Constraint> = children
+                .iter()
+                .map(|child| match (child.size(), split) {
+                    _ if lone => Constraint::Fill(1),
+                    (PaneSize::Fixed(cells), _) => Constraint::Length(cells),
+                    (PaneSize::Percent(percent), _) => Constraint::Percentage(percent),
+                    (PaneSize::Auto, SplitDirection::Vertical) => child
+                        .preferred_height(height)
+                        .map_or(Constraint::Fill(1), Constraint::Length),
+                    (PaneSize::Auto, SplitDirection::Horizontal) | (PaneSize::Fill, _) => {
+                        Constraint::Fill(1)
+                    }
+                })
+                .collect();
+            let direction = match split {
+                SplitDirection::Vertical => Direction::Vertical,
+                SplitDirection::Horizontal => Direction::Horizontal,
+            };
+            let areas = Layout::default()
+                .direction(direction)
+                .constraints(constraints)
+                .split(area);
+            children
+                .iter()
+                .zip(areas.iter())
+                .flat_map(|(child, area)| place(child, *area, height))
+                .collect()
+        }
+    }
+}
+
+/// Built-in workspaces, switched with the number keys in this order.
+pub fn default_workspaces() -> Vec<WorkspaceConfig> {
+    use LayoutNode as Node;
+    use PaneSize::{Auto, Fill, Percent};
+    use SplitDirection::{Horizontal, Vertical};
+
+    let workspace = |name: &str, panes: Vec<LayoutNode>| WorkspaceConfig {
+        name: name.to_string(),
+        layout: Node::split(Vertical, Fill, panes),
+    };
+    vec![
+        workspace(
+            "funding",
+            vec![
+                Node::pane("header", Auto),
+                Node::split(
+                    Horizontal,
+                    Fill,
+                    vec![
+                        Node::pane("table", Fill),
+                        Node::pane("term_structure", Percent(35)),
+                    ],
+                ),
+                Node::split(
+                    Horizontal,
+                    Auto,
+                    vec![
+                        Node::pane("opportunities", Percent(60)),
+                        Node::pane("vol_surface", Percent(40)),
+                    ],
+                ),
+                Node::split(
+                    Horizontal,
+                    Auto,
+                    vec![Node::pane("alerts", Percent(40)), Node::pane("log", Fill)],
+                ),
+            ],
+        ),
+        workspace(
+            "basis",
+            vec![
+                Node::pane("header", Auto),
+                Node::split(
+                    Horizontal,
+                    Fill,
+                    vec![
+                        Node::pane("table", Percent(55)),
+                        Node::pane("term_structure", Fill),
+                    ],
+                ),
+                Node::pane("opportunities", Auto),
+                Node::pane("alerts", Auto),
+            ],
+        ),
+        workspace(
+            "vol",
+            vec![
+                Node::pane("header", Auto),
+                Node::split(
+                    Horizontal,
+                    Percent(60),
+                    vec![
+                        Node::pane("vol_surface", Percent(50)),
+                        Node::pane("term_structure", Fill),
+                    ],
+                ),
+                Node::pane("table", Fill),
+            ],
+        ),
+        workspace(
+            "heatmap",
+            vec![
+                Node::pane("header", Auto),
+                Node::pane("heatmap", Auto),
+                Node::pane("table", Fill),
+                Node::pane("alerts", Auto),
+            ],
+        ),
+        workspace(
+            "health",
+            vec![
+                Node::pane("header", Auto),
+                Node::pane("health", Fill),
+                Node::pane("log", Percent(35)),
+                Node::pane("alerts", Auto),
+            ],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pane_sizes_round_trip_through_display() {
+        for raw in ["fill", "auto", "12", "35%", "0"] {
+            assert_eq!(raw.parse::<PaneSize>().unwrap().to_string(), raw);
+        }
+        assert_eq!(" 40 % ".parse::<PaneSize>().unwrap(), PaneSize::Percent(40));
+    }
+
+    #[test]
+    fn invalid_pane_sizes_are_rejected() {
+        for raw in ["", "half", "-3", "x%", "70000"] {
+            assert!(raw.parse::<PaneSize>().is_err(), "{raw:?} parsed");
+        }
+    }
+
+    #[test]
+    fn hidden_panes_give_their_space_to_the_rest() {
+        let layout = LayoutNode::split(
+            SplitDirection::Vertical,
+            PaneSize::Fill,
+            vec![
+                LayoutNode::pane("table", PaneSize::Fill),
+                LayoutNode::pane("alerts", PaneSize::Auto),
+                LayoutNode::pane("log", PaneSize::Fixed(10)),
+            ],
+        );
+        let area = Rect::new(0, 0, 80, 40);
+        let height = |pane: &str| match pane {
+            "alerts" => Some(6),
+            "log" => Some(0),
+            _ => None,
+        };
+        let placed = place(&layout, area, &height);
+        let heights: Vec<(&str, u16)> = placed
+            .iter()
+            .map(|(pane, rect)| (pane.as_str(), rect.height))
+            .collect();
+        assert_eq!(heights, [("table", 34), ("alerts", 6)]);
+    }
+}
@@ -1,13 +1,59 @@
 pub mod ai;
 pub mod alerts;
 pub mod app;
+pub mod asset;
+pub mod commands;
 pub mod config;
 pub mod data;
+pub mod discovery;
+pub mod etf;
+pub mod events;
+pub mod forecast;
+pub mod health;
+pub mod history;
+pub mod keymap;
+pub mod layout;
 pub mod metrics;
+pub mod persist;
+pub mod ratelimit;
+pub mod scanner;
+pub mod theme;
 pub mod ui;
+pub mod vol;
 
 pub use app::QuantumDesk;
 
+use config::AppConfig;
+use data::DataHub;
+use discovery::InstrumentInfo;
+
+/// Runs the UI, then saves state. A UI error takes precedence; a failed
+/// save after one is only printed, so the original cause is not lost.
 pub fn run(mut app: QuantumDesk) -> anyhow::Result<()> {
-    ui::run(&mut app)
+    let result = ui::run(&mut app);
+    let shutdown = app.shutdown();
+    match (result, shutdown) {
+        (Err(err), Err(shutdown_err)) => {
+            eprintln!("warning: {:#}", shutdown_err);
+            Err(err)
+        }
+        (result, shutdown) => result.and(shutdown),
+    }
+}
+
+/// Searches the cached venue listings, re-listing venues first when the
+/// local copy is stale. Listing failures are returned alongside the matches.
+pub fn search_symbols(
+    config: &AppConfig,
+    query: &str,
+) -> anyhow::Result<(Vec<InstrumentInfo>, Vec<String>)> {
+    let mut hub = DataHub::new();
+    hub.load_directory(&config.instruments_path())?;
+    let warnings = if hub.directory().is_stale() {
+        hub.refresh_directory(&config.venues, &config.instruments_path())
+    } else {
+        Vec::new()
+    };
+    let matches = hub.directory().search(query).into_iter().cloned().collect();
+    Ok((matches, warnings))
 }
@@ -1,28 +1,102 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
 use quantumdesk::QuantumDesk;
+use quantumdesk::config::AppConfig;
+use quantumdesk::metrics::StatsWindow;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "QuantumDesk - AI-powered funding monitor", long_about = None)]
 struct Cli {
-    /// Milliseconds between UI refresh ticks
-    #[arg(long = "refresh-ms", default_value_t = 1_000)]
-    refresh_ms: u64,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// TOML config file (defaults to the platform config dir)
+    #[arg(long = "config")]
+    config: Option<PathBuf>,
+
+    /// Milliseconds between UI refresh ticks (default 1000)
+    #[arg(long = "refresh-ms")]
+    refresh_ms: Option<u64>,
 
-    /// Seconds to cache exchange responses before re-fetching
-    #[arg(long = "cache-ttl", default_value_t = 60)]
-    cache_ttl_secs: u64,
+    /// Seconds to cache exchange responses before re-fetching (default 60)
+    #[arg(long = "cache-ttl")]
+    cache_ttl_secs: Option<u64>,
 
     /// Render a condensed layout with minimal chrome
     #[arg(long = "compact", default_value_t = false)]
     compact: bool,
+
+    /// Show rolling funding statistics for a window (1d, 7d or 30d)
+    #[arg(long = "stats-window")]
+    stats_window: Option<StatsWindow>,
+
+    /// Hours of holding period used to rank carry opportunities
+    #[arg(long = "carry-horizon")]
+    carry_horizon_hours: Option<u64>,
+
+    /// Underlying to track, e.g. ETH; repeat for several (default BTC)
+    #[arg(long = "asset")]
+    assets: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Browse venue instrument listings
+    Symbols {
+        #[command(subcommand)]
+        action: SymbolsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SymbolsCommand {
+    /// Find instruments by venue, symbol, asset or kind, e.g. `eth perp`
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    let config = quantumdesk::config::AppConfig::with_overrides(
-        Some(cli.refresh_ms),
-        Some(cli.cache_ttl_secs),
+    let config = AppConfig::load(cli.config.as_deref())?.with_overrides(
+        cli.refresh_ms,
+        cli.cache_ttl_secs,
         cli.compact,
+        cli.stats_window,
+        cli.carry_horizon_hours,
+        cli.assets,
     );
-    quantumdesk::run(QuantumDesk::new(config))
+    match cli.command {
+        Some(Command::Symbols {
+            action: SymbolsCommand::Search { query },
+        }) => search(&config, &query.join(" ")),
+        None => quantumdesk::run(QuantumDesk::new(config)),
+    }
+}
+
+fn search(config: &AppConfig, query: &str) -> anyhow::Result<()> {
+    let (matches, warnings) = quantumdesk::search_symbols(config, query)?;
+    for warning in &warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if matches.is_empty() {
+        println!("No instruments match {:?}", query);
+    }
+    for info in matches {
+        let expiry = info
+            .expiry
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        println!(
+            "{:<9} {:<24} {:<8} {:<7} {}",
+            info.venue,
+            info.symbol,
+            info.asset,
+            format!("{:?}", info.kind),
+            expiry
+        );
+    }
+    Ok(())
 }
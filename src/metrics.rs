@@ -1,28 +1,1008 @@
-use crate::data::MarketSnapshot;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{RealizedVolConfig, VenueConfig};
+use crate::data::{InstrumentKind, MarketSnapshot, OptionChain};
+use crate::history::{COMPACT_SPACING_SECS, HistorySample, SnapshotHistory};
+use crate::vol::VolSurface;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+/// Funding interval assumed for venues missing from the config.
+const DEFAULT_FUNDING_INTERVAL_HOURS: u64 = 8;
+/// Fewer aligned returns than this make a correlation meaningless.
+const MIN_CORRELATION_POINTS: usize = 5;
 
 #[derive(Debug, Default)]
-pub struct MetricsEngine;
+pub struct MetricsEngine {
+    history: SnapshotHistory,
+    realized_vol: RealizedVolConfig,
+}
 
 impl MetricsEngine {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(realized_vol: RealizedVolConfig) -> Self {
+        Self {
+            history: SnapshotHistory::new(),
+            realized_vol,
+        }
+    }
+
+    pub fn history(&self) -> &SnapshotHistory {
+        &self.history
+    }
+
+    pub fn load_history(&mut self, path: &Path) -> Result<()> {
+        self.history = SnapshotHistory::load(path)?;
+        Ok(())
     }
 
-    pub fn summarize(&self, snapshots: &[MarketSnapshot]) -> MetricsSummary {
-        if snapshots.is_empty() {
-            return MetricsSummary::default();
+    pub fn save_history(&mut self, path: &Path) -> Result<()> {
+        self.history.save(path)
+    }
+
+    /// Feeds freshly collected snapshots into the rolling history.
+    pub fn record(&mut self, snapshots: &[MarketSnapshot]) {
+        self.history.record(snapshots);
+    }
+
+    pub fn summarize(
+        &self,
+        snapshots: &[MarketSnapshot],
+        options: &[OptionChain],
+        venues: &[VenueConfig],
+    ) -> MetricsSummary {
+        let now = Utc::now();
+        let instruments: Vec<InstrumentMetrics> = snapshots
+            .iter()
+            .map(|snapshot| {
+                let periods = funding_periods_per_year(venues, &snapshot.venue);
+                let mut metrics = instrument_metrics(snapshot, periods, now);
+                if snapshot.is_perp() {
+                    metrics.funding_stats = StatsWindow::ALL
+                        .iter()
+                        .filter_map(|window| self.funding_stats(snapshot, *window))
+                        .collect();
+                }
+                metrics
+            })
+            .collect();
+
+        // Assets in the order their rows arrive, then any with options only.
+        let mut assets: Vec<&str> = Vec::new();
+        for asset in snapshots
+            .iter()
+            .map(|s| s.asset.as_str())
+            .chain(options.iter().map(|chain| chain.asset.as_str()))
+        {
+            if !assets.contains(&asset) {
+                assets.push(asset);
+            }
         }
 
-        let funding_sum: f64 = snapshots.iter().map(|s| s.funding_rate).sum();
         MetricsSummary {
             venues_online: snapshots.len(),
-            average_funding_rate: funding_sum / snapshots.len() as f64,
+            assets: assets
+                .into_iter()
+                .map(|asset| asset_metrics(asset, snapshots, &instruments, options, now))
+                .collect(),
+            instruments,
+            realized_vol: self.realized_vols(snapshots),
+            correlations: self.correlations(snapshots),
+        }
+    }
+
+    fn bar_length(&self) -> Duration {
+        Duration::minutes(self.realized_vol.bar_minutes.max(1) as i64)
+    }
+
+    fn windows(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.realized_vol
+            .windows_hours
+            .iter()
+            .map(|hours| Duration::hours(*hours as i64))
+    }
+
+    fn bars(&self, snapshot: &MarketSnapshot, window: Duration) -> Vec<PriceBar> {
+        resample(
+            self.history
+                .window(&snapshot.venue, &snapshot.symbol, window),
+            self.bar_length(),
+        )
+    }
+
+    fn realized_vols(&self, snapshots: &[MarketSnapshot]) -> Vec<RealizedVol> {
+        let bars_per_year =
+            Duration::days(365).num_seconds() as f64 / self.bar_length().num_seconds() as f64;
+        let mut results = Vec::new();
+        for snapshot in snapshots.iter().filter(|s| !s.is_dated_future()) {
+            for window in self.windows() {
+                let bars = self.bars(snapshot, window);
+                let close_to_close = close_to_close_variance(&bars);
+                let parkinson = parkinson_variance(&bars);
+                if close_to_close.is_none() && parkinson.is_none() {
+                    continue;
+                }
+                results.push(RealizedVol {
+                    asset: snapshot.asset.clone(),
+                    venue: snapshot.venue.clone(),
+                    symbol: snapshot.symbol.clone(),
+                    kind: snapshot.kind,
+                    window_hours: window.num_hours(),
+                    bars: bars.len(),
+                    close_to_close: close_to_close.map(|var| (var * bars_per_year).sqrt()),
+                    parkinson: parkinson.map(|var| (var * bars_per_year).sqrt()),
+                });
+            }
+        }
+        results
+    }
+
+    fn correlations(&self, snapshots: &[MarketSnapshot]) -> Vec<Correlation> {
+        let series: Vec<&MarketSnapshot> =
+            snapshots.iter().filter(|s| !s.is_dated_future()).collect();
+        let mut results = Vec::new();
+        for window in self.windows() {
+            let returns: Vec<BTreeMap<i64, f64>> = series
+                .iter()
+                .map(|snapshot| bar_returns(&self.bars(snapshot, window)))
+                .collect();
+            for (idx, left) in series.iter().enumerate() {
+                for (offset, right) in series[idx + 1..].iter().enumerate() {
+                    let Some(coefficient) = pearson(&returns[idx], &returns[idx + 1 + offset])
+                    else {
+                        continue;
+                    };
+                    results.push(Correlation {
+                        left: format!("{}:{}", left.venue, left.symbol),
+                        right: format!("{}:{}", right.venue, right.symbol),
+                        window_hours: window.num_hours(),
+                        coefficient,
+                    });
+                }
+            }
+        }
+        results
+    }
+
+    fn funding_stats(
+        &self,
+        snapshot: &MarketSnapshot,
+        window: StatsWindow,
+    ) -> Option<FundingStats> {
+        let series: Vec<(DateTime<Utc>, f64)> = self
+            .history
+            .window(&snapshot.venue, &snapshot.symbol, window.duration())
+            .map(|sample| (sample.at, sample.funding_rate))
+            .collect();
+        FundingStats::from_series(window, &series, snapshot.funding_rate, Utc::now())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PriceBar {
+    slot: i64,
+    high: f64,
+    low: f64,
+    close: f64,
+    samples: usize,
+}
+
+/// Buckets samples into fixed-length OHLC bars keyed by bar slot.
+fn resample<'a>(samples: impl Iterator<Item = &'a HistorySample>, bar: Duration) -> Vec<PriceBar> {
+    let bar_secs = bar.num_seconds();
+    let mut bars: Vec<PriceBar> = Vec::new();
+    for sample in samples.filter(|sample| sample.price > 0.0) {
+        let slot = sample.at.timestamp().div_euclid(bar_secs);
+        match bars.last_mut() {
+            Some(last) if last.slot == slot => {
+                last.high = last.high.max(sample.price);
+                last.low = last.low.min(sample.price);
+                last.close = sample.price;
+                last.samples += 1;
+            }
+            _ => bars.push(PriceBar {
+                slot,
+                high: sample.price,
+                low: sample.price,
+                close: sample.price,
+                samples: 1,
+            }),
+        }
+    }
+    bars
+}
+
+/// Log returns between consecutive bars, keyed by the later bar's slot.
+fn bar_returns(bars: &[PriceBar]) -> BTreeMap<i64, f64> {
+    bars.windows(2)
+        .filter(|pair| pair[1].slot == pair[0].slot + 1)
+        .map(|pair| (pair[1].slot, (pair[1].close / pair[0].close).ln()))
+        .collect()
+}
+
+fn close_to_close_variance(bars: &[PriceBar]) -> Option<f64> {
+    let returns: Vec<f64> = bar_returns(bars).into_values().collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    Some(returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64)
+}
+
+/// Parkinson high/low estimator; only bars with an intrabar range count.
+fn parkinson_variance(bars: &[PriceBar]) -> Option<f64> {
+    let ranges: Vec<f64> = bars
+        .iter()
+        .filter(|bar| bar.samples > 1 && bar.low > 0.0)
+        .map(|bar| (bar.high / bar.low).ln().powi(2))
+        .collect();
+    if ranges.len() < 2 {
+        return None;
+    }
+    Some(ranges.iter().sum::<f64>() / (4.0 * std::f64::consts::LN_2 * ranges.len() as f64))
+}
+
+fn pearson(left: &BTreeMap<i64, f64>, right: &BTreeMap<i64, f64>) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = left
+        .iter()
+        .filter_map(|(slot, l)| right.get(slot).map(|r| (*l, *r)))
+        .collect();
+    if pairs.len() < MIN_CORRELATION_POINTS {
+        return None;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_l = pairs.iter().map(|(l, _)| l).sum::<f64>() / count;
+    let mean_r = pairs.iter().map(|(_, r)| r).sum::<f64>() / count;
+    let (mut cov, mut var_l, mut var_r) = (0.0, 0.0, 0.0);
+    for (l, r) in &pairs {
+        cov += (l - mean_l) * (r - mean_r);
+        var_l += (l - mean_l).powi(2);
+        var_r += (r - mean_r).powi(2);
+    }
+    let denom = (var_l * var_r).sqrt();
+    (denom > f64::EPSILON).then(|| cov / denom)
+}
+
+fn asset_metrics(
+    asset: &str,
+    snapshots: &[MarketSnapshot],
+    instruments: &[InstrumentMetrics],
+    options: &[OptionChain],
+    now: DateTime<Utc>,
+) -> AssetMetrics {
+    let rows: Vec<(&MarketSnapshot, &InstrumentMetrics)> = snapshots
+        .iter()
+        .zip(instruments)
+        .filter(|(snapshot, _)| snapshot.asset == asset)
+        .collect();
+    let perps: Vec<&MarketSnapshot> = rows
+        .iter()
+        .map(|(snapshot, _)| *snapshot)
+        .filter(|s| s.is_perp())
+        .collect();
+    let average_funding_rate = if perps.is_empty() {
+        0.0
+    } else {
+        perps.iter().map(|s| s.funding_rate).sum::<f64>() / perps.len() as f64
+    };
+    let annualized: Vec<f64> = rows
+        .iter()
+        .filter_map(|(_, metrics)| metrics.annualized_funding)
+        .collect();
+    let annualized_perp_funding = if annualized.is_empty() {
+        0.0
+    } else {
+        annualized.iter().sum::<f64>() / annualized.len() as f64
+    };
+
+    AssetMetrics {
+        asset: asset.to_string(),
+        perps_online: perps.len(),
+        average_funding_rate,
+        annualized_perp_funding,
+        oi_weighted_funding_rate: weighted_funding(&perps, |s| s.open_interest),
+        volume_weighted_funding_rate: weighted_funding(&perps, |s| s.volume_24h),
+        open_interest_shares: open_interest_shares(&perps),
+        term_structure: term_structure(&rows),
+        spreads: cross_venue_spreads(&perps),
+        vol: options
+            .iter()
+            .find(|chain| chain.asset == asset)
+            .map(|chain| VolSurface::from_chain(chain, now)),
+    }
+}
+
+fn weighted_funding(
+    perps: &[&MarketSnapshot],
+    weight: impl Fn(&MarketSnapshot) -> Option<f64>,
+) -> Option<f64> {
+    let (weighted_sum, total) = perps
+        .iter()
+        .filter_map(|perp| weight(perp).map(|w| (perp.funding_rate, w)))
+        .filter(|(_, w)| *w > 0.0)
+        .fold((0.0, 0.0), |(sum, total), (rate, w)| {
+            (sum + rate * w, total + w)
+        });
+    (total > 0.0).then(|| weighted_sum / total)
+}
+
+fn open_interest_shares(perps: &[&MarketSnapshot]) -> Vec<VenueShare> {
+    let mut by_venue: Vec<VenueShare> = Vec::new();
+    for perp in perps {
+        let Some(oi) = perp.open_interest.filter(|oi| *oi > 0.0) else {
+            continue;
+        };
+        match by_venue.iter_mut().find(|entry| entry.venue == perp.venue) {
+            Some(entry) => entry.open_interest += oi,
+            None => by_venue.push(VenueShare {
+                venue: perp.venue.clone(),
+                open_interest: oi,
+                share: 0.0,
+            }),
+        }
+    }
+
+    let total: f64 = by_venue.iter().map(|entry| entry.open_interest).sum();
+    for entry in &mut by_venue {
+        entry.share = entry.open_interest / total;
+    }
+    by_venue.sort_by(|a, b| b.share.total_cmp(&a.share));
+    by_venue
+}
+
+/// Funding settlements per year on `venue`, from its configured interval.
+/// Perp premia converge over one interval, so perp basis annualizes on the
+/// same cycle.
+pub fn funding_periods_per_year(venues: &[VenueConfig], venue: &str) -> f64 {
+    let hours = venues
+        .iter()
+        .find(|config| config.name == venue)
+        .map_or(DEFAULT_FUNDING_INTERVAL_HOURS, |config| {
+            config.funding_interval_hours
+        })
+        .max(1);
+    DAYS_PER_YEAR * 24.0 / hours as f64
+}
+
+fn instrument_metrics(
+    snapshot: &MarketSnapshot,
+    periods_per_year: f64,
+    now: DateTime<Utc>,
+) -> InstrumentMetrics {
+    let reference = snapshot.reference_price();
+    let basis = snapshot
+        .perp_price
+        .filter(|_| reference > 0.0)
+        .map(|perp| perp - reference);
+    let basis_bps = basis.map(|basis| basis / reference * 10_000.0);
+    let premium_bps = match (snapshot.mark_price, snapshot.index_price) {
+        (Some(mark), Some(index)) if index > 0.0 => Some((mark - index) / index * 10_000.0),
+        _ => None,
+    };
+
+    let days_to_expiry = snapshot
+        .expiry
+        .map(|expiry| (expiry - now).num_seconds() as f64 / 86_400.0);
+    let annualized_basis = match days_to_expiry {
+        Some(days) if days > 0.0 => basis_bps.map(|bps| bps / 10_000.0 * DAYS_PER_YEAR / days),
+        Some(_) => None,
+        None => basis_bps.map(|bps| bps / 10_000.0 * periods_per_year),
+    };
+
+    InstrumentMetrics {
+        asset: snapshot.asset.clone(),
+        venue: snapshot.venue.clone(),
+        symbol: snapshot.symbol.clone(),
+        funding_rate: snapshot.is_perp().then_some(snapshot.funding_rate),
+        predicted_funding_rate: snapshot.predicted_funding_rate,
+        annualized_funding: snapshot
+            .is_perp()
+            .then_some(snapshot.funding_rate * periods_per_year),
+        basis,
+        basis_bps,
+        annualized_basis,
+        premium_bps,
+        days_to_expiry,
+        funding_stats: Vec::new(),
+    }
+}
+
+fn term_structure(rows: &[(&MarketSnapshot, &InstrumentMetrics)]) -> Vec<TermPoint> {
+    let mut points: Vec<TermPoint> = rows
+        .iter()
+        .filter(|(snapshot, _)| snapshot.is_dated_future())
+        .filter_map(|(snapshot, metrics)| {
+            Some(TermPoint {
+                venue: snapshot.venue.clone(),
+                symbol: snapshot.symbol.clone(),
+                expiry: snapshot.expiry?,
+                days_to_expiry: metrics.days_to_expiry?,
+                annualized_basis: metrics.annualized_basis?,
+            })
+        })
+        .collect();
+    points.sort_by_key(|point| point.expiry);
+    points
+}
+
+fn cross_venue_spreads(perps: &[&MarketSnapshot]) -> Vec<VenueSpread> {
+    let mut spreads = Vec::new();
+    for (idx, left) in perps.iter().enumerate() {
+        for right in &perps[idx + 1..] {
+            if left.venue == right.venue {
+                continue;
+            }
+            let (Some(left_price), Some(right_price)) = (left.perp_price, right.perp_price) else {
+                continue;
+            };
+            let mid = (left_price + right_price) / 2.0;
+            if mid <= 0.0 {
+                continue;
+            }
+            spreads.push(VenueSpread {
+                base_venue: left.venue.clone(),
+                base_symbol: left.symbol.clone(),
+                quote_venue: right.venue.clone(),
+                quote_symbol: right.symbol.clone(),
+                spread_bps: (left_price - right_price) / mid * 10_000.0,
+                funding_differential: left.funding_rate - right.funding_rate,
+            });
         }
     }
+    spreads
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct MetricsSummary {
     pub venues_online: usize,
+    pub instruments: Vec<InstrumentMetrics>,
+    /// Per-underlying aggregates, in configured asset order.
+    pub assets: Vec<AssetMetrics>,
+    pub realized_vol: Vec<RealizedVol>,
+    pub correlations: Vec<Correlation>,
+}
+
+impl MetricsSummary {
+    pub fn instrument(&self, venue: &str, symbol: &str) -> Option<&InstrumentMetrics> {
+        self.instruments
+            .iter()
+            .find(|metrics| metrics.venue == venue && metrics.symbol == symbol)
+    }
+
+    pub fn asset(&self, asset: &str) -> Option<&AssetMetrics> {
+        self.assets
+            .iter()
+            .find(|metrics| metrics.asset.eq_ignore_ascii_case(asset))
+    }
+}
+
+/// Funding, open interest, curve and volatility aggregates for one asset.
+/// Spreads and weights only ever compare perps on the same underlying.
+#[derive(Debug, Default, Clone)]
+pub struct AssetMetrics {
+    pub asset: String,
+    pub perps_online: usize,
+    /// Mean funding across perps only; spot and index rows carry no funding.
     pub average_funding_rate: f64,
+    /// Mean of the perps' annualized funding, each on its venue's cycle, on
+    /// the same annual scale as the futures curve.
+    pub annualized_perp_funding: f64,
+    /// Perp funding weighted by open interest; `None` until any venue reports OI.
+    pub oi_weighted_funding_rate: Option<f64>,
+    /// Perp funding weighted by 24h volume; `None` until any venue reports volume.
+    pub volume_weighted_funding_rate: Option<f64>,
+    /// Each venue's share of total perp open interest, largest first.
+    pub open_interest_shares: Vec<VenueShare>,
+    pub spreads: Vec<VenueSpread>,
+    /// Dated futures ordered by expiry.
+    pub term_structure: Vec<TermPoint>,
+    pub vol: Option<VolSurface>,
+}
+
+/// Derived pricing metrics for one snapshot row. Fields are `None` when the
+/// row has no perp leg or the venue does not publish the needed prices.
+#[derive(Debug, Default, Clone)]
+pub struct InstrumentMetrics {
+    pub asset: String,
+    pub venue: String,
+    pub symbol: String,
+    /// Current funding, only set for perps.
+    pub funding_rate: Option<f64>,
+    pub predicted_funding_rate: Option<f64>,
+    /// Current funding as an annual fraction over the venue's funding
+    /// cycle, the same cycle perp basis annualizes over.
+    pub annualized_funding: Option<f64>,
+    /// Perp price minus index, in quote currency.
+    pub basis: Option<f64>,
+    pub basis_bps: Option<f64>,
+    /// Basis as an annual fraction (0.10 = 10%/yr). Dated futures annualize
+    /// over time to expiry, perps over the funding cycle.
+    pub annualized_basis: Option<f64>,
+    /// Mark price premium over the venue index.
+    pub premium_bps: Option<f64>,
+    pub days_to_expiry: Option<f64>,
+    pub funding_stats: Vec<FundingStats>,
+}
+
+impl InstrumentMetrics {
+    pub fn stats(&self, window: StatsWindow) -> Option<&FundingStats> {
+        self.funding_stats
+            .iter()
+            .find(|stats| stats.window == window)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatsWindow {
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+}
+
+impl StatsWindow {
+    pub const ALL: [StatsWindow; 3] = [Self::Day, Self::Week, Self::Month];
+
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::days(7),
+            Self::Month => Duration::days(30),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Day => "1d",
+            Self::Week => "7d",
+            Self::Month => "30d",
+        }
+    }
+}
+
+impl fmt::Display for StatsWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for StatsWindow {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|window| window.label().eq_ignore_ascii_case(raw))
+            .ok_or_else(|| format!("unknown stats window {:?} (expected 1d, 7d or 30d)", raw))
+    }
+}
+
+/// Rolling funding statistics over one window, with the current rate placed
+/// in the context of that window.
+#[derive(Debug, Clone)]
+pub struct FundingStats {
+    pub window: StatsWindow,
+    pub samples: usize,
+    pub mean: f64,
+    pub ewma: f64,
+    pub std_dev: f64,
+    /// `None` while the window has no dispersion to scale by.
+    pub z_score: Option<f64>,
+    /// Share of the window's time spent at or below the current rate,
+    /// 0.0..=1.0.
+    pub percentile: f64,
+}
+
+impl FundingStats {
+    /// Stats over `series` (oldest first) up to `end`. History is denser
+    /// for the last day than before it, so each sample is weighted by the
+    /// time it covers: the series is resampled onto a uniform grid first.
+    fn from_series(
+        window: StatsWindow,
+        series: &[(DateTime<Utc>, f64)],
+        current: f64,
+        end: DateTime<Utc>,
+    ) -> Option<Self> {
+        let rates = uniform_grid(series, Duration::seconds(COMPACT_SPACING_SECS), end);
+        if rates.is_empty() {
+            return None;
+        }
+
+        let count = rates.len() as f64;
+        let mean = rates.iter().sum::<f64>() / count;
+        let variance = rates.iter().map(|rate| (rate - mean).powi(2)).sum::<f64>() / count;
+        let std_dev = variance.sqrt();
+
+        let alpha = 2.0 / (count + 1.0);
+        let ewma = rates[1..]
+            .iter()
+            .fold(rates[0], |acc, rate| alpha * rate + (1.0 - alpha) * acc);
+
+        let at_or_below = rates.iter().filter(|rate| **rate <= current).count();
+
+        Some(Self {
+            window,
+            samples: series.len(),
+            mean,
+            ewma,
+            std_dev,
+            z_score: (std_dev > f64::EPSILON).then(|| (current - mean) / std_dev),
+            percentile: at_or_below as f64 / count,
+        })
+    }
+}
+
+/// Values of a step series (oldest first) read every `step` from its first
+/// point through `end`, each point holding until the next one.
+fn uniform_grid(series: &[(DateTime<Utc>, f64)], step: Duration, end: DateTime<Utc>) -> Vec<f64> {
+    let Some(&(mut at, _)) = series.first() else {
+        return Vec::new();
+    };
+    let mut values = Vec::new();
+    let mut index = 0;
+    loop {
+        while series.get(index + 1).is_some_and(|(next, _)| *next <= at) {
+            index += 1;
+        }
+        values.push(series[index].1);
+        at += step;
+        if at > end {
+            break;
+        }
+    }
+    values
+}
+
+/// One expiry on the futures curve.
+#[derive(Debug, Clone)]
+pub struct TermPoint {
+    pub venue: String,
+    pub symbol: String,
+    pub expiry: DateTime<Utc>,
+    pub days_to_expiry: f64,
+    pub annualized_basis: f64,
+}
+
+/// Annualized realized volatility of one price series over a window.
+#[derive(Debug, Clone)]
+pub struct RealizedVol {
+    pub asset: String,
+    pub venue: String,
+    pub symbol: String,
+    pub kind: InstrumentKind,
+    pub window_hours: i64,
+    pub bars: usize,
+    pub close_to_close: Option<f64>,
+    /// High/low estimator from intrabar snapshot ranges.
+    pub parkinson: Option<f64>,
+}
+
+/// Correlation of bar log returns between two price series.
+#[derive(Debug, Clone)]
+pub struct Correlation {
+    pub left: String,
+    pub right: String,
+    pub window_hours: i64,
+    pub coefficient: f64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct VenueShare {
+    pub venue: String,
+    pub open_interest: f64,
+    /// Fraction of total perp open interest, 0.0..=1.0.
+    pub share: f64,
+}
+
+/// Price and funding gap between perps of the same underlying on two venues,
+/// expressed as `base - quote`.
+#[derive(Debug, Default, Clone)]
+pub struct VenueSpread {
+    pub base_venue: String,
+    pub base_symbol: String,
+    pub quote_venue: String,
+    pub quote_symbol: String,
+    pub spread_bps: f64,
+    pub funding_differential: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::minutes(minutes)
+    }
+
+    /// `days` of 5-minute samples at `old`, then a day of 30s samples at
+    /// `recent`, the density `SnapshotHistory` keeps after compaction.
+    fn mixed_density(days: i64, old: f64, recent: f64) -> Vec<(DateTime<Utc>, f64)> {
+        let split = days * 24 * 60;
+        let mut series: Vec<(DateTime<Utc>, f64)> =
+            (0..split / 5).map(|step| (at(step * 5), old)).collect();
+        series.extend((0..24 * 120).map(|step| (at(split) + Duration::seconds(step * 30), recent)));
+        series
+    }
+
+    #[test]
+    fn stats_weight_samples_by_time_covered() {
+        let series = mixed_density(6, 0.0001, 0.0008);
+        let end = at(7 * 24 * 60);
+        let stats = FundingStats::from_series(StatsWindow::Week, &series, 0.0008, end).unwrap();
+        // One day in seven at the recent rate, however densely sampled.
+        assert!((stats.mean - (6.0 * 0.0001 + 0.0008) / 7.0).abs() < 1e-6);
+        assert!((stats.percentile - 1.0).abs() < 1e-9);
+        assert_eq!(stats.samples, series.len());
+        let below = FundingStats::from_series(StatsWindow::Week, &series, 0.0005, end).unwrap();
+        assert!((below.percentile - 6.0 / 7.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn z_score_is_not_dragged_by_dense_recent_samples() {
+        let series = mixed_density(6, 0.0001, 0.0001);
+        let end = at(7 * 24 * 60);
+        let flat = FundingStats::from_series(StatsWindow::Week, &series, 0.0003, end).unwrap();
+        assert!(flat.z_score.is_none());
+        assert!((flat.ewma - 0.0001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sparse_points_hold_until_the_next_one() {
+        let series = [(at(0), 1.0), (at(10), 3.0)];
+        let grid = uniform_grid(&series, Duration::minutes(5), at(20));
+        assert_eq!(grid, [1.0, 1.0, 3.0, 3.0, 3.0]);
+        assert!(uniform_grid(&[], Duration::minutes(5), at(20)).is_empty());
+    }
+    fn venues(binance_hours: u64) -> Vec<VenueConfig> {
+        let mut venues = crate::config::AppConfig::default().venues;
+        for venue in &mut venues {
+            if venue.name == "Binance" {
+                venue.funding_interval_hours = binance_hours;
+            }
+        }
+        venues
+    }
+
+    fn perp(venue: &str, symbol: &str, index: f64, price: f64, funding: f64) -> MarketSnapshot {
+        let mut snapshot = MarketSnapshot::placeholder(
+            venue,
+            "Perp",
+            symbol,
+            index,
+            Some(price),
+            funding,
+            None,
+            None,
+        );
+        snapshot.index_price = Some(index);
+        snapshot
+    }
+
+    fn future(symbol: &str, index: f64, price: f64, expiry: DateTime<Utc>) -> MarketSnapshot {
+        let mut snapshot = perp("Deribit", symbol, index, price, 0.0);
+        snapshot.kind = InstrumentKind::Future;
+        snapshot.instrument_label = "Future".into();
+        snapshot.expiry = Some(expiry);
+        snapshot
+    }
+
+    fn summarize(snapshots: &[MarketSnapshot], binance_hours: u64) -> MetricsSummary {
+        MetricsEngine::default().summarize(snapshots, &[], &venues(binance_hours))
+    }
+
+    #[test]
+    fn periods_per_year_follow_the_venue_interval() {
+        let venues = venues(1);
+        assert_eq!(funding_periods_per_year(&venues, "Binance"), 8_760.0);
+        assert_eq!(funding_periods_per_year(&venues, "Deribit"), 1_095.0);
+        assert_eq!(funding_periods_per_year(&venues, "Elsewhere"), 1_095.0);
+    }
+
+    #[test]
+    fn perp_basis_and_funding_annualize_over_the_funding_cycle() {
+        let snapshot = perp("Binance", "BTCUSDT", 100.0, 100.1, 0.0001);
+        let hourly = instrument_metrics(&snapshot, 8_760.0, at(0));
+        assert!((hourly.basis_bps.unwrap() - 10.0).abs() < 1e-9);
+        assert!((hourly.annualized_basis.unwrap() - 8.76).abs() < 1e-9);
+        assert!((hourly.annualized_funding.unwrap() - 0.876).abs() < 1e-9);
+
+        let summary = summarize(&[snapshot], 4);
+        let row = summary.instrument("Binance", "BTCUSDT").unwrap();
+        assert!((row.annualized_funding.unwrap() - 0.0001 * 2_190.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dated_basis_annualizes_over_time_to_expiry() {
+        let now = at(0);
+        let snapshot = future("BTC-27DEC24", 100.0, 101.0, now + Duration::days(73));
+        let metrics = instrument_metrics(&snapshot, 1_095.0, now);
+        assert!((metrics.days_to_expiry.unwrap() - 73.0).abs() < 1e-9);
+        assert!((metrics.annualized_basis.unwrap() - 0.01 * 365.0 / 73.0).abs() < 1e-9);
+        assert_eq!(metrics.funding_rate, None);
+        assert_eq!(metrics.annualized_funding, None);
+
+        let expired = future("BTC-01JAN24", 100.0, 101.0, now - Duration::hours(1));
+        assert_eq!(
+            instrument_metrics(&expired, 1_095.0, now).annualized_basis,
+            None
+        );
+    }
+
+    #[test]
+    fn premium_is_mark_over_index() {
+        let mut snapshot = perp("Deribit", "BTC-PERPETUAL", 200.0, 200.0, 0.0);
+        snapshot.mark_price = Some(199.0);
+        let metrics = instrument_metrics(&snapshot, 1_095.0, at(0));
+        assert!((metrics.premium_bps.unwrap() + 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn funding_is_weighted_by_open_interest_and_volume() {
+        let mut big = perp("Binance", "BTCUSDT", 100.0, 100.0, 0.0003);
+        big.open_interest = Some(300.0);
+        big.volume_24h = Some(100.0);
+        let mut small = perp("Deribit", "BTC-PERPETUAL", 100.0, 100.0, 0.0001);
+        small.open_interest = Some(100.0);
+        small.volume_24h = Some(300.0);
+        let unreported = perp("Bitfinex", "tBTCF0:USTF0", 100.0, 100.0, 0.0009);
+
+        let summary = summarize(&[big, small, unreported], 8);
+        let asset = summary.asset("BTC").unwrap();
+        assert_eq!(asset.perps_online, 3);
+        assert!((asset.average_funding_rate - 0.0013 / 3.0).abs() < 1e-12);
+        assert!((asset.oi_weighted_funding_rate.unwrap() - 0.00025).abs() < 1e-12);
+        assert!((asset.volume_weighted_funding_rate.unwrap() - 0.00015).abs() < 1e-12);
+        let shares: Vec<(&str, f64)> = asset
+            .open_interest_shares
+            .iter()
+            .map(|share| (share.venue.as_str(), share.share))
+            .collect();
+        assert_eq!(shares, [("Binance", 0.75), ("Deribit", 0.25)]);
+    }
+
+    #[test]
+    fn spreads_compare_perps_across_venues_only() {
+        let summary = summarize(
+            &[
+                perp("Binance", "BTCUSDT", 100.0, 101.0, 0.0003),
+                perp("Deribit", "BTC-PERPETUAL", 100.0, 99.0, 0.0001),
+                perp("Deribit", "BTC_USDC-PERPETUAL", 100.0, 99.0, 0.0001),
+            ],
+            8,
+        );
+        let spreads = &summary.asset("BTC").unwrap().spreads;
+        assert_eq!(spreads.len(), 2);
+        assert!((spreads[0].spread_bps - 200.0).abs() < 1e-9);
+        assert!((spreads[0].funding_differential - 0.0002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn term_structure_lists_dated_futures_by_expiry() {
+        let now = Utc::now();
+        let summary = summarize(
+            &[
+                future("BTC-27DEC30", 100.0, 105.0, now + Duration::days(200)),
+                perp("Deribit", "BTC-PERPETUAL", 100.0, 100.0, 0.0001),
+                future("BTC-27JUN30", 100.0, 102.0, now + Duration::days(20)),
+            ],
+            8,
+        );
+        let curve: Vec<&str> = summary
+            .asset("BTC")
+            .unwrap()
+            .term_structure
+            .iter()
+            .map(|point| point.symbol.as_str())
+            .collect();
+        assert_eq!(curve, ["BTC-27JUN30", "BTC-27DEC30"]);
+    }
+
+    fn price(minutes: i64, price: f64) -> HistorySample {
+        HistorySample {
+            at: at(minutes),
+            funding_rate: 0.0,
+            price,
+            reference_price: price,
+        }
+    }
+
+    fn bars(closes: &[f64]) -> Vec<PriceBar> {
+        let samples: Vec<HistorySample> = closes
+            .iter()
+            .enumerate()
+            .map(|(idx, close)| price(idx as i64 * 5, *close))
+            .collect();
+        resample(samples.iter(), Duration::minutes(5))
+    }
+
+    fn returns(closes: &[f64]) -> BTreeMap<i64, f64> {
+        bar_returns(&bars(closes))
+    }
+
+    #[test]
+    fn resample_builds_ohlc_bars_per_slot() {
+        let samples = [
+            price(0, 100.0),
+            price(2, 104.0),
+            price(4, 98.0),
+            price(5, 101.0),
+            price(6, 0.0),
+            price(15, 103.0),
+        ];
+        let bars = resample(samples.iter(), Duration::minutes(5));
+        let shape: Vec<(f64, f64, f64, usize)> = bars
+            .iter()
+            .map(|bar| (bar.high, bar.low, bar.close, bar.samples))
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                (104.0, 98.0, 98.0, 3),
+                (101.0, 101.0, 101.0, 1),
+                (103.0, 103.0, 103.0, 1)
+            ]
+        );
+        assert_eq!(bars[1].slot, bars[0].slot + 1);
+        assert_eq!(bars[2].slot, bars[0].slot + 3);
+
+        let returns = bar_returns(&bars);
+        assert_eq!(
+            returns.len(),
+            1,
+            "the gap between slots must not yield a return"
+        );
+        assert!((returns[&bars[1].slot] - (101.0_f64 / 98.0).ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn close_to_close_variance_is_the_sample_variance_of_log_returns() {
+        let up = 1.01_f64;
+        let closes = [100.0, 100.0 * up, 100.0, 100.0 * up, 100.0];
+        let log = up.ln();
+        // Returns alternate +log/-log around a zero mean: sum of squares 4*log^2 over n-1 = 3.
+        let expected = 4.0 * log * log / 3.0;
+        let variance = close_to_close_variance(&bars(&closes)).unwrap();
+        assert!((variance - expected).abs() < 1e-15);
+        assert!(close_to_close_variance(&bars(&[100.0, 101.0])).is_none());
+    }
+
+    #[test]
+    fn parkinson_needs_two_bars_with_a_range() {
+        let samples = [
+            price(0, 100.0),
+            price(1, 110.0),
+            price(5, 100.0),
+            price(6, 110.0),
+            price(10, 100.0),
+        ];
+        let bars = resample(samples.iter(), Duration::minutes(5));
+        let expected = (110.0_f64 / 100.0).ln().powi(2) / (4.0 * std::f64::consts::LN_2);
+        assert!((parkinson_variance(&bars).unwrap() - expected).abs() < 1e-15);
+        assert!(parkinson_variance(&bars[1..]).is_none());
+    }
+
+    #[test]
+    fn pearson_tracks_the_direction_of_comovement() {
+        let left = returns(&[100.0, 101.0, 99.0, 102.0, 101.0, 104.0]);
+        let doubled: BTreeMap<i64, f64> = left.iter().map(|(slot, r)| (*slot, 2.0 * r)).collect();
+        let inverted: BTreeMap<i64, f64> = left.iter().map(|(slot, r)| (*slot, -r)).collect();
+        assert!((pearson(&left, &doubled).unwrap() - 1.0).abs() < 1e-12);
+        assert!((pearson(&left, &inverted).unwrap() + 1.0).abs() < 1e-12);
+
+        let short: BTreeMap<i64, f64> = left.iter().take(4).map(|(k, v)| (*k, *v)).collect();
+        assert!(pearson(&short, &doubled).is_none());
+        let flat: BTreeMap<i64, f64> = left.keys().map(|slot| (*slot, 0.0)).collect();
+        assert!(pearson(&left, &flat).is_none());
+    }
 }
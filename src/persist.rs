@@ -0,0 +1,33 @@
+//! JSON state files under the data directory.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Reads `path` as JSON; a missing file is `None`. `what` names the file in
+/// error messages.
+pub fn load_json<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw =
+        fs::read(path).with_context(|| format!("failed to read {} {}", what, path.display()))?;
+    serde_json::from_slice(&raw)
+        .map(Some)
+        .with_context(|| format!("failed to parse {} {}", what, path.display()))
+}
+
+/// Writes `value` to `path` via a temp file and rename, so a crash never
+/// leaves a half-written file behind.
+pub fn save_json<T: Serialize>(path: &Path, value: &T, what: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let raw = serde_json::to_vec(value).with_context(|| format!("failed to encode {}", what))?;
+    fs::write(&tmp, raw).with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))
+}
@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::config::VenueConfig;
+
+/// How long to back off after a 429/418 that carries no `Retry-After`.
+const DEFAULT_BACKOFF_SECS: i64 = 30;
+
+/// Returned by `acquire` when a request is held back locally. It says
+/// nothing about the venue's health, so the circuit breaker ignores it.
+#[derive(Debug)]
+pub struct BudgetExhausted {
+    pub venue: String,
+    pub wait: Duration,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // No countdown: the event log folds repeats of an identical message.
+        write!(f, "{} rate budget exhausted; serving cache", self.venue)
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// Token bucket sized in the venue's request-weight units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub capacity: f64,
+    /// Weight restored per second.
+    pub refill_per_sec: f64,
+    /// Weight per endpoint, matched as a substring of the request URL. The
+    /// longest match wins, so `ticker/24hr?symbol=` can override
+    /// `ticker/24hr`; unlisted endpoints cost 1.
+    pub weights: BTreeMap<String, f64>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 60.0,
+            refill_per_sec: 1.0,
+            weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn weight(&self, url: &str) -> f64 {
+        self.weights
+            .iter()
+            .filter(|(endpoint, _)| url.contains(endpoint.as_str()))
+            .max_by_key(|(endpoint, _)| endpoint.len())
+            .map_or(1.0, |(_, weight)| *weight)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    config: RateLimitConfig,
+    tokens: f64,
+    refilled_at: DateTime<Utc>,
+    /// Set by `Retry-After` or a 429; nothing is sent before it passes.
+    blocked_until: Option<DateTime<Utc>>,
+    /// Weight spent since the current tick began; the estimate for the next.
+    spent_this_tick: f64,
+}
+
+impl TokenBucket {
+    fn new(config: RateLimitConfig, now: DateTime<Utc>) -> Self {
+        Self {
+            tokens: config.capacity,
+            config,
+            refilled_at: now,
+            blocked_until: None,
+            spent_this_tick: 0.0,
+        }
+    }
+
+    fn refill(&mut self, now: DateTime<Utc>) {
+        let elapsed = (now - self.refilled_at).num_milliseconds().max(0) as f64 / 1_000.0;
+        self.tokens =
+            (self.tokens + elapsed * self.config.refill_per_sec).min(self.config.capacity);
+        self.refilled_at = now;
+    }
+
+    /// Time until the bucket refills enough to spend `weight`.
+    fn refill_wait(&self, weight: f64) -> Duration {
+        let deficit = (weight.min(self.config.capacity) - self.tokens).max(0.0);
+        if deficit > 0.0 && self.config.refill_per_sec > 0.0 {
+            Duration::milliseconds((deficit / self.config.refill_per_sec * 1_000.0).ceil() as i64)
+        } else {
+            Duration::zero()
+        }
+    }
+
+    /// Time until `weight` can be spent, including any venue-imposed block.
+    fn wait_for(&self, weight: f64, now: DateTime<Utc>) -> Duration {
+        let blocked = self.blocked_until.map_or(Duration::zero(), |until| {
+            (until - now).max(Duration::zero())
+        });
+        blocked.max(self.refill_wait(weight))
+    }
+
+    fn block(&mut self, until: DateTime<Utc>) {
+        self.blocked_until = Some(self.blocked_until.map_or(until, |known| known.max(until)));
+    }
+}
+
+/// Parses `Retry-After` as delta-seconds or an HTTP-date.
+fn retry_after(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return Some(now + Duration::milliseconds((secs.max(0.0) * 1_000.0) as i64));
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Per-venue request budgets. Every venue call asks `acquire` first and
+/// reports the response back through `observe`, so server-side limits and
+/// `Retry-After` tighten the local bucket.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Creates buckets for new venues and picks up changed limits.
+    pub fn configure(&mut self, venues: &[VenueConfig]) {
+        let now = Utc::now();
+        for venue in venues {
+            self.buckets
+                .entry(venue.name.clone())
+                .and_modify(|bucket| bucket.config = venue.rate_limit.clone())
+                .or_insert_with(|| TokenBucket::new(venue.rate_limit.clone(), now));
+        }
+    }
+
+    /// Starts a new tick's spend accounting.
+    pub fn begin_tick(&mut self) {
+        for bucket in self.buckets.values_mut() {
+            bucket.spent_this_tick = 0.0;
+        }
+    }
+
+    /// Spends the weight of `url` from the venue budget, or fails without
+    /// sending when the budget is exhausted or the venue asked us to wait.
+    pub fn acquire(&mut self, venue: &str, url: &str) -> Result<()> {
+        let Some(bucket) = self.buckets.get_mut(venue) else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        bucket.refill(now);
+        let weight = bucket.config.weight(url);
+        let wait = bucket.wait_for(weight, now);
+        if wait > Duration::zero() {
+            return Err(BudgetExhausted {
+                venue: venue.to_string(),
+                wait,
+            }
+            .into());
+        }
+        bucket.tokens -= weight;
+        bucket.spent_this_tick += weight;
+        Ok(())
+    }
+
+    /// Applies `Retry-After`, Binance used-weight and generic
+    /// `X-RateLimit-Remaining` headers, and backs off on 429/418.
+    pub fn observe(&mut self, venue: &str, status: StatusCode, headers: &HeaderMap) {
+        let Some(bucket) = self.buckets.get_mut(venue) else {
+            return;
+        };
+        let now = Utc::now();
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let header = |name: &str| text(name).and_then(|value| value.parse::<f64>().ok());
+
+        if let Some(used) = header("x-mbx-used-weight-1m") {
+            bucket.tokens = bucket.tokens.min(bucket.config.capacity - used);
+        }
+        if let Some(remaining) = header("x-ratelimit-remaining") {
+            bucket.tokens = bucket.tokens.min(remaining);
+        }
+        if let Some(until) = text("retry-after").and_then(|value| retry_after(value, now)) {
+            bucket.block(until);
+        } else if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            bucket.block(now + Duration::seconds(DEFAULT_BACKOFF_SECS));
+        }
+    }
+
+    /// When a venue-imposed block (`Retry-After`, 429) on `venue` lifts.
+    pub fn blocked_until(&self, venue: &str) -> Option<DateTime<Utc>> {
+        self.buckets
+            .get(venue)
+            .and_then(|bucket| bucket.blocked_until)
+            .filter(|until| Utc::now() < *until)
+    }
+
+    /// How long to stretch the next tick so every venue can afford what the
+    /// last one spent. Venues blocked by `Retry-After` serve cache meanwhile
+    /// and do not hold back the others.
+    pub fn tick_delay(&self) -> Duration {
+        let now = Utc::now();
+        self.buckets
+            .values()
+            .map(|bucket| {
+                let mut bucket = bucket.clone();
+                bucket.refill(now);
+                bucket.refill_wait(bucket.spent_this_tick)
+            })
+            .max()
+            .unwrap_or_else(Duration::zero)
+    }
+
+    /// Remaining budget per venue as a fraction of capacity.
+    pub fn remaining(&self) -> Vec<(String, f64)> {
+        let now = Utc::now();
+        let mut remaining: Vec<(String, f64)> = self
+            .buckets
+            .iter()
+            .map(|(venue, bucket)| {
+                let mut bucket = bucket.clone();
+                bucket.refill(now);
+                let blocked = bucket.blocked_until.is_some_and(|until| until > now);
+                let share = if blocked || bucket.config.capacity <= 0.0 {
+                    0.0
+                } else {
+                    (bucket.tokens / bucket.config.capacity).clamp(0.0, 1.0)
+                };
+                (venue.clone(), share)
+            })
+            .collect();
+        remaining.sort_by(|a, b| a.0.cmp(&b.0));
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:27:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            retry_after("1.5", now),
+            Some(now + Duration::milliseconds(1_500))
+        );
+        assert_eq!(
+            retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(now + Duration::seconds(60))
+        );
+        assert_eq!(retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn longest_endpoint_weight_wins() {
+        let venues = crate::config::AppConfig::default().venues;
+        let binance = venues.iter().find(|venue| venue.name == "Binance").unwrap();
+        let config = &binance.rate_limit;
+        let base = "https://fapi.binance.com/fapi/v1/";
+        assert_eq!(config.weight(&format!("{base}ticker/24hr")), 40.0);
+        assert_eq!(
+            config.weight(&format!("{base}ticker/24hr?symbol=BTCUSDT")),
+            1.0
+        );
+        assert_eq!(config.weight(&format!("{base}klines")), 1.0);
+    }
+}
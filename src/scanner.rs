@@ -0,0 +1,298 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::VenueConfig;
+use crate::data::{InstrumentKind, MarketSnapshot};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeTier {
+    Maker,
+    Taker,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarryKind {
+    /// Long spot, short perp: collects the perp's funding.
+    SpotPerp,
+    /// Short the richer perp, long the cheaper one on another venue.
+    PerpPerp,
+}
+
+impl CarryKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SpotPerp => "Spot/Perp",
+            Self::PerpPerp => "Perp/Perp",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CarryLeg {
+    pub venue: String,
+    pub symbol: String,
+}
+
+/// A ranked carry trade. Carry, fees and net figures are fractions of
+/// notional over the scanner horizon.
+#[derive(Debug, Clone)]
+pub struct CarryOpportunity {
+    pub kind: CarryKind,
+    pub long: CarryLeg,
+    pub short: CarryLeg,
+    pub gross_carry: f64,
+    pub fees: f64,
+    pub net_carry: f64,
+    pub annualized_net: f64,
+}
+
+/// Ranks carry trades across every venue pair on the same asset by expected
+/// net carry over `horizon`, counting funding settlements that fall inside
+/// it and paying entry plus exit fees on both legs.
+#[derive(Debug, Clone)]
+pub struct OpportunityScanner {
+    horizon: Duration,
+    fee_tier: FeeTier,
+}
+
+impl OpportunityScanner {
+    pub fn new(horizon_hours: u64, fee_tier: FeeTier) -> Self {
+        Self {
+            horizon: Duration::hours(horizon_hours.max(1) as i64),
+            fee_tier,
+        }
+    }
+
+    pub fn horizon_hours(&self) -> i64 {
+        self.horizon.num_hours()
+    }
+
+    pub fn scan(
+        &self,
+        snapshots: &[MarketSnapshot],
+        venues: &[VenueConfig],
+    ) -> Vec<CarryOpportunity> {
+        let now = Utc::now();
+        let spots: Vec<&MarketSnapshot> = snapshots
+            .iter()
+            .filter(|snapshot| snapshot.kind == InstrumentKind::Spot)
+            .collect();
+        let perps: Vec<&MarketSnapshot> = snapshots.iter().filter(|s| s.is_perp()).collect();
+
+        let mut opportunities = Vec::new();
+        for perp in &perps {
+            let perp_income = self.funding_income(perp, venues, now);
+            for spot in spots.iter().filter(|spot| spot.asset == perp.asset) {
+                let fees = 2.0 * (self.fee(&spot.venue, venues) + self.fee(&perp.venue, venues));
+                opportunities.push(self.opportunity(
+                    CarryKind::SpotPerp,
+                    spot,
+                    perp,
+                    perp_income,
+                    fees,
+                ));
+            }
+        }
+
+        for (idx, left) in perps.iter().enumerate() {
+            for right in &perps[idx + 1..] {
+                if left.venue == right.venue || left.asset != right.asset {
+                    continue;
+                }
+                let left_income = self.funding_income(left, venues, now);
+                let right_income = self.funding_income(right, venues, now);
+                let (long, short, gross) = if left_income >= right_income {
+                    (right, left, left_income - right_income)
+                } else {
+                    (left, right, right_income - left_income)
+                };
+                let fees = 2.0 * (self.fee(&left.venue, venues) + self.fee(&right.venue, venues));
+                opportunities.push(self.opportunity(CarryKind::PerpPerp, long, short, gross, fees));
+            }
+        }
+
+        opportunities.sort_by(|a, b| b.net_carry.total_cmp(&a.net_carry));
+        opportunities
+    }
+
+    fn opportunity(
+        &self,
+        kind: CarryKind,
+        long: &MarketSnapshot,
+        short: &MarketSnapshot,
+        gross_carry: f64,
+        fees: f64,
+    ) -> CarryOpportunity {
+        let net_carry = gross_carry - fees;
+        let periods_per_year = 365.0 * 24.0 / self.horizon.num_hours() as f64;
+        CarryOpportunity {
+            kind,
+            long: CarryLeg {
+                venue: long.venue.clone(),
+                symbol: long.symbol.clone(),
+            },
+            short: CarryLeg {
+                venue: short.venue.clone(),
+                symbol: short.symbol.clone(),
+            },
+            gross_carry,
+            fees,
+            net_carry,
+            annualized_net: net_carry * periods_per_year,
+        }
+    }
+
+    /// Funding a short position on `perp` collects over the horizon.
+    fn funding_income(
+        &self,
+        perp: &MarketSnapshot,
+        venues: &[VenueConfig],
+        now: DateTime<Utc>,
+    ) -> f64 {
+        let interval_hours = venue_config(&perp.venue, venues)
+            .map(|venue| venue.funding_interval_hours)
+            .unwrap_or(8)
+            .max(1);
+        let settlements = expected_settlements(
+            now,
+            now + self.horizon,
+            perp.next_funding_time,
+            Duration::hours(interval_hours as i64),
+        );
+        perp.funding_rate * settlements as f64
+    }
+
+    fn fee(&self, venue: &str, venues: &[VenueConfig]) -> f64 {
+        let bps = venue_config(venue, venues)
+            .map(|venue| match self.fee_tier {
+                FeeTier::Maker => venue.maker_fee_bps,
+                FeeTier::Taker => venue.taker_fee_bps,
+            })
+            .unwrap_or(0.0);
+        bps / 10_000.0
+    }
+}
+
+fn venue_config<'a>(venue: &str, venues: &'a [VenueConfig]) -> Option<&'a VenueConfig> {
+    venues.iter().find(|config| config.name == venue)
+}
+
+/// Settlements in `[start, end]`, stepping from the venue's next settlement
+/// when known, otherwise assuming an even spread over the window.
+fn expected_settlements(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    next_funding: Option<DateTime<Utc>>,
+    interval: Duration,
+) -> u32 {
+    match next_funding {
+        Some(mut settlement) => {
+            while settlement < start {
+                settlement += interval;
+            }
+            let mut count = 0;
+            while settlement <= end {
+                count += 1;
+                settlement += interval;
+            }
+            count
+        }
+        None => ((end - start).num_seconds() / interval.num_seconds()) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn snapshot(venue: &str, label: &str, symbol: &str, funding: f64) -> MarketSnapshot {
+        MarketSnapshot::placeholder(
+            venue,
+            label,
+            symbol,
+            100.0,
+            Some(100.0),
+            funding,
+            None,
+            None,
+        )
+    }
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::hours(hours)
+    }
+
+    #[test]
+    fn opportunities_rank_by_carry_net_of_fees() {
+        let venues = AppConfig::default().venues;
+        let snapshots = [
+            snapshot("Bitfinex", "Spot", "tBTCUSD", 0.0),
+            snapshot("Binance", "Perp", "BTCUSDT", 0.0003),
+            snapshot("Deribit", "Perp", "BTC-PERPETUAL", 0.0001),
+        ];
+        let ranked = OpportunityScanner::new(24, FeeTier::Taker).scan(&snapshots, &venues);
+        let order: Vec<(CarryKind, &str, &str)> = ranked
+            .iter()
+            .map(|opportunity| {
+                (
+                    opportunity.kind,
+                    opportunity.long.venue.as_str(),
+                    opportunity.short.venue.as_str(),
+                )
+            })
+            .collect();
+        // The spot/perp trade collects the most funding but pays Bitfinex's
+        // 20 bps taker fee on the way in and out.
+        assert_eq!(
+            order,
+            [
+                (CarryKind::PerpPerp, "Deribit", "Binance"),
+                (CarryKind::SpotPerp, "Bitfinex", "Binance"),
+                (CarryKind::SpotPerp, "Bitfinex", "Deribit"),
+            ]
+        );
+        let best = &ranked[0];
+        assert!((best.gross_carry - 0.0006).abs() < 1e-12);
+        assert!((best.fees - 0.002).abs() < 1e-12);
+        assert!((best.net_carry + 0.0014).abs() < 1e-12);
+        assert!((best.annualized_net + 0.0014 * 365.0).abs() < 1e-9);
+        let spot = &ranked[1];
+        assert!((spot.gross_carry - 0.0009).abs() < 1e-12);
+        assert!((spot.fees - 0.005).abs() < 1e-12);
+    }
+
+    #[test]
+    fn maker_tier_uses_maker_fees() {
+        let venues = AppConfig::default().venues;
+        let snapshots = [
+            snapshot("Binance", "Perp", "BTCUSDT", 0.0003),
+            snapshot("Deribit", "Perp", "BTC-PERPETUAL", 0.0001),
+        ];
+        let ranked = OpportunityScanner::new(24, FeeTier::Maker).scan(&snapshots, &venues);
+        assert_eq!(ranked.len(), 1);
+        assert!((ranked[0].fees - 2.0 * 0.0002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn settlements_step_from_the_next_known_one() {
+        let interval = Duration::hours(8);
+        assert_eq!(
+            expected_settlements(at(0), at(24), Some(at(2)), interval),
+            3
+        );
+        assert_eq!(
+            expected_settlements(at(0), at(24), Some(at(-6)), interval),
+            3
+        );
+        assert_eq!(
+            expected_settlements(at(0), at(24), Some(at(9)), interval),
+            2
+        );
+        assert_eq!(expected_settlements(at(0), at(12), None, interval), 1);
+    }
+}
@@ -0,0 +1,313 @@
+//! Semantic UI colors: built-in presets, per-color overrides from config and
+//! a monochrome mode for `NO_COLOR` terminals.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// No colors; emphasis through bold, dim, italic and reverse video.
+    Mono,
+}
+
+/// A color from config: a name (`"lightred"`), `"#rrggbb"` or a 0-255
+/// terminal palette index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThemeColor(pub Color);
+
+impl FromStr for ThemeColor {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        raw.trim().parse().map(Self).map_err(|_| {
+            anyhow!(
+                "invalid color {:?} (expected a name, #rrggbb or 0-255)",
+                raw
+            )
+        })
+    }
+}
+
+impl TryFrom<String> for ThemeColor {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        raw.parse()
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(color: ThemeColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Overrides on top of the preset; unset entries keep the preset's color.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    pub positive: Option<ThemeColor>,
+    pub negative: Option<ThemeColor>,
+    pub stale: Option<ThemeColor>,
+    pub warning: Option<ThemeColor>,
+    pub alert_triggered: Option<ThemeColor>,
+    pub border: Option<ThemeColor>,
+    pub header: Option<ThemeColor>,
+    pub muted: Option<ThemeColor>,
+    pub selection: Option<ThemeColor>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub colors: ThemeColors,
+}
+
+/// Resolved colors the renderer draws with.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub positive: Color,
+    pub negative: Color,
+    /// Data served from cache past its TTL.
+    pub stale: Color,
+    pub warning: Color,
+    pub alert_triggered: Color,
+    pub border: Color,
+    pub header: Color,
+    /// Secondary text: status line, hints, quiet rows.
+    pub muted: Color,
+    /// Background of the highlighted entry in pickers and the palette.
+    pub selection: Color,
+    pub selection_text: Color,
+    /// Chart lines, in the order series are drawn.
+    pub series: [Color; 4],
+    monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::Dark)
+    }
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                positive: Color::Green,
+                negative: Color::Red,
+                stale: Color::Yellow,
+                warning: Color::Yellow,
+                alert_triggered: Color::Red,
+                border: Color::Reset,
+                header: Color::Cyan,
+                muted: Color::Gray,
+                selection: Color::Cyan,
+                selection_text: Color::Black,
+                series: [Color::Yellow, Color::Cyan, Color::Magenta, Color::Green],
+                monochrome: false,
+            },
+            ThemePreset::Light => Self {
+                positive: Color::Rgb(0, 128, 0),
+                negative: Color::Rgb(192, 0, 0),
+                stale: Color::Rgb(176, 96, 0),
+                warning: Color::Rgb(176, 96, 0),
+                alert_triggered: Color::Rgb(192, 0, 0),
+                border: Color::DarkGray,
+                header: Color::Blue,
+                muted: Color::DarkGray,
+                selection: Color::Blue,
+                selection_text: Color::White,
+                series: [
+                    Color::Rgb(176, 96, 0),
+                    Color::Blue,
+                    Color::Magenta,
+                    Color::Rgb(0, 128, 0),
+                ],
+                monochrome: false,
+            },
+            ThemePreset::HighContrast => Self {
+                positive: Color::LightGreen,
+                negative: Color::LightRed,
+                stale: Color::LightYellow,
+                warning: Color::LightYellow,
+                alert_triggered: Color::LightRed,
+                border: Color::White,
+                header: Color::LightCyan,
+                muted: Color::White,
+                selection: Color::LightYellow,
+                selection_text: Color::Black,
+                series: [
+                    Color::LightYellow,
+                    Color::LightCyan,
+                    Color::LightMagenta,
+                    Color::LightGreen,
+                ],
+                monochrome: false,
+            },
+            ThemePreset::Mono => Self {
+                positive: Color::Reset,
+                negative: Color::Reset,
+                stale: Color::Reset,
+                warning: Color::Reset,
+                alert_triggered: Color::Reset,
+                border: Color::Reset,
+                header: Color::Reset,
+                muted: Color::Reset,
+                selection: Color::Reset,
+                selection_text: Color::Reset,
+                series: [Color::Reset; 4],
+                monochrome: true,
+            },
+        }
+    }
+
+    /// The configured preset with its overrides applied. A set `NO_COLOR`
+    /// (https://no-color.org) wins over both.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        if no_color() {
+            return Self::preset(ThemePreset::Mono);
+        }
+        let mut theme = Self::preset(config.preset);
+        let colors = &config.colors;
+        for (slot, color) in [
+            (&mut theme.positive, colors.positive),
+            (&mut theme.negative, colors.negative),
+            (&mut theme.stale, colors.stale),
+            (&mut theme.warning, colors.warning),
+            (&mut theme.alert_triggered, colors.alert_triggered),
+            (&mut theme.border, colors.border),
+            (&mut theme.header, colors.header),
+            (&mut theme.muted, colors.muted),
+            (&mut theme.selection, colors.selection),
+        ] {
+            if let Some(ThemeColor(color)) = color {
+                *slot = color;
+            }
+        }
+        theme
+    }
+
+    /// `color` as a foreground, or `emphasis` when colors are off.
+    fn fg(&self, color: Color, emphasis: Modifier) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(emphasis)
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    pub fn positive(&self) -> Style {
+        self.fg(self.positive, Modifier::empty())
+    }
+
+    pub fn negative(&self) -> Style {
+        self.fg(self.negative, Modifier::BOLD)
+    }
+
+    /// Positive or negative style by sign; zero is unstyled.
+    pub fn signed(&self, value: f64) -> Style {
+        if value > 0.0 {
+            self.positive()
+        } else if value < 0.0 {
+            self.negative()
+        } else {
+            Style::default()
+        }
+    }
+
+    pub fn stale(&self) -> Style {
+        self.fg(self.stale, Modifier::ITALIC)
+    }
+
+    pub fn warning(&self) -> Style {
+        self.fg(self.warning, Modifier::BOLD)
+    }
+
+    pub fn alert_triggered(&self) -> Style {
+        self.fg(self.alert_triggered, Modifier::BOLD)
+    }
+
+    pub fn border(&self) -> Style {
+        self.fg(self.border, Modifier::empty())
+    }
+
+    pub fn header(&self) -> Style {
+        self.fg(self.header, Modifier::empty())
+    }
+
+    pub fn muted(&self) -> Style {
+        self.fg(self.muted, Modifier::DIM)
+    }
+
+    pub fn selected(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(self.selection_text).bg(self.selection)
+        }
+    }
+
+    /// Bordered panel block in the theme's border color.
+    pub fn block<'a>(&self) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.border())
+    }
+
+    /// Border of the pane that has keyboard focus.
+    pub fn focused(&self) -> Style {
+        self.fg(self.selection, Modifier::BOLD)
+    }
+
+    /// Diverging scale for `intensity` in [-1, 1]: negative through neutral
+    /// to positive, with the strongest values filled in.
+    pub fn diverging(&self, intensity: f64) -> Style {
+        let strength = intensity.abs();
+        let color = if intensity < 0.0 {
+            self.negative
+        } else {
+            self.positive
+        };
+        if strength < 0.1 {
+            self.muted()
+        } else if self.monochrome {
+            if strength >= 0.5 {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            }
+        } else if strength >= 0.5 {
+            Style::default().fg(self.selection_text).bg(color)
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    /// Style of the `index`th chart series, cycling through the palette.
+    pub fn series(&self, index: usize) -> Style {
+        self.fg(self.series[index % self.series.len()], Modifier::empty())
+    }
+}
+
+fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
@@ -1,52 +1,116 @@
+use std::collections::HashMap;
 use std::io::{self, Stdout};
-use std::time::Duration;
+use std::panic;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind,
+};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::layout::{Constraint, Direction, Flex, Layout, Margin, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Axis, Block, Cell, Chart, Clear, Dataset, GraphType, Paragraph, Row, Sparkline, Table,
+    TableState,
+};
 use ratatui::{Frame, Terminal};
 
-use crate::app::QuantumDesk;
+use crate::app::{CommandPalette, Heatmap, QuantumDesk, SortColumn, SymbolPicker};
+use crate::data::{InstrumentKind, MarketSnapshot};
+use crate::events::Severity;
+use crate::health::{FeedSource, FeedStats};
+use crate::history::HistorySample;
+use crate::keymap::{Action, KeyBinding};
+use crate::layout;
+use crate::metrics::{AssetMetrics, FundingStats, MetricsSummary, RealizedVol, StatsWindow};
+use crate::theme::Theme;
 
 pub fn run(app: &mut QuantumDesk) -> Result<()> {
+    run_with_panes(app, &PaneRegistry::default())
+}
+
+/// Runs the UI with extra pane types available to workspace layouts.
+pub fn run_with_panes(app: &mut QuantumDesk, panes: &PaneRegistry) -> Result<()> {
+    let mouse = app.mouse_enabled();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal(mouse);
+        default_hook(info);
+    }));
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if app.mouse_enabled() {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    let result = run_loop(app, &mut terminal);
+    let result = run_loop(app, &mut terminal, panes);
 
     disable_raw_mode()?;
+    if app.mouse_enabled() {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
+    // Back to the default hook; the terminal is the shell's again.
+    let _ = panic::take_hook();
 
     result
 }
 
+/// Leaves the alternate screen, raw mode and mouse reporting, ignoring
+/// errors; runs from the panic hook so a crash does not wreck the shell.
+fn restore_terminal(mouse: bool) {
+    let mut stdout = io::stdout();
+    if mouse {
+        let _ = execute!(stdout, DisableMouseCapture);
+    }
+    let _ = execute!(stdout, LeaveAlternateScreen, crossterm::cursor::Show);
+    let _ = disable_raw_mode();
+}
+
 fn run_loop(
     app: &mut QuantumDesk,
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    panes: &PaneRegistry,
 ) -> Result<()> {
+    let mut placed = Vec::new();
+    let mut last_tick = Instant::now();
     loop {
-        terminal.draw(|frame| draw(frame, app))?;
+        terminal.draw(|frame| placed = draw(frame, app, panes))?;
 
-        let poll_timeout = Duration::from_millis(app.refresh_interval_ms().max(100));
+        // Input only redraws; data is collected once the (possibly
+        // stretched) refresh interval has passed or a refresh was forced.
+        let interval = Duration::from_millis(app.next_tick_ms());
+        let poll_timeout = interval.saturating_sub(last_tick.elapsed());
         if event::poll(poll_timeout)? {
             match event::read()? {
-                Event::Key(key) => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => break,
-                    _ => {}
-                },
+                Event::Key(key) if app.state.palette.is_some() => handle_palette_key(app, key),
+                Event::Key(key) if app.state.picker.is_some() => handle_picker_key(app, key.code),
+                Event::Key(key) if app.state.table.editing_filter => {
+                    handle_filter_key(app, key.code)
+                }
+                Event::Key(_) if app.state.show_help => app.state.show_help = false,
+                Event::Key(key) => {
+                    if let Some(action) = app.keymap().action(&key)
+                        && !handle_action(app, action)
+                    {
+                        break;
+                    }
+                }
+                Event::Mouse(mouse) => handle_mouse(app, mouse, &placed),
                 Event::Resize(_, _) => {
                     // re-render on next iteration automatically
                 }
@@ -54,169 +118,1802 @@ fn run_loop(
             }
         }
 
-        app.tick()?;
+        if app.take_refresh_request() || last_tick.elapsed() >= interval {
+            last_tick = Instant::now();
+            app.tick()?;
+        }
     }
 
     Ok(())
 }
 
-fn draw(frame: &mut Frame, app: &QuantumDesk) {
+/// Runs a bound action; `false` when it quits.
+fn handle_action(app: &mut QuantumDesk, action: Action) -> bool {
+    let heatmap = app.focused_pane() == "heatmap";
+    let log = app.focused_pane() == "log";
+    match action {
+        Action::Quit => return false,
+        Action::Back if app.state.show_detail => app.close_detail(),
+        Action::Back => return false,
+        Action::Open if app.state.show_detail => app.close_detail(),
+        Action::Open if heatmap => app.open_heatmap_cell(),
+        Action::Open if log => {}
+        Action::Open => app.open_detail(),
+        Action::Help => app.state.show_help = true,
+        Action::Palette => app.open_palette(),
+        Action::AddInstrument => app.open_symbol_picker(),
+        Action::ToggleHealth => app.toggle_health_pane(),
+        Action::StatsWindow => app.cycle_stats_window(),
+        Action::FocusNext => app.cycle_focus(),
+        Action::Pause => app.toggle_pause(),
+        Action::Refresh => app.force_refresh(None),
+        Action::RefreshVenue => app.refresh_selected_venue(),
+        Action::Workspace(index) => app.switch_workspace(index),
+        Action::Down if heatmap => app.move_heatmap_cursor(1, 0),
+        Action::Up if heatmap => app.move_heatmap_cursor(-1, 0),
+        Action::Left if heatmap => app.move_heatmap_cursor(0, -1),
+        Action::Right if heatmap => app.move_heatmap_cursor(0, 1),
+        Action::Down if log => app.scroll_log(1),
+        Action::Up if log => app.scroll_log(-1),
+        Action::Down => app.move_table_selection(1),
+        Action::Up => app.move_table_selection(-1),
+        Action::Left | Action::Right => {}
+        Action::SortNext => app.cycle_table_sort(),
+        Action::SortReverse => app.reverse_table_sort(),
+        Action::Filter => app.state.table.editing_filter = true,
+        Action::HeatmapMetric => app.toggle_heatmap_metric(),
+        Action::LogSeverity => app.cycle_log_filter(),
+    }
+    true
+}
+
+/// Clicks select, sort and focus; the wheel moves through the table or
+/// heatmap and scrolls the alerts and log panes.
+fn handle_mouse(app: &mut QuantumDesk, mouse: MouseEvent, placed: &[(String, Rect)]) {
+    if app.state.show_help {
+        if let MouseEventKind::Down(_) = mouse.kind {
+            app.state.show_help = false;
+        }
+        return;
+    }
+    if app.state.palette.is_some()
+        || app.state.picker.is_some()
+        || app.state.table.editing_filter
+        || app.state.show_detail
+    {
+        return;
+    }
+    let Some((pane, area)) = placed.iter().find(|(_, area)| {
+        (area.x..area.x + area.width).contains(&mouse.column)
+            && (area.y..area.y + area.height).contains(&mouse.row)
+    }) else {
+        return;
+    };
+    let scroll = match mouse.kind {
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::ScrollUp => -1,
+        MouseEventKind::Down(MouseButton::Left) => 0,
+        _ => return,
+    };
+    match (pane.as_str(), scroll) {
+        ("table", 0) => {
+            app.focus_pane(pane);
+            if let Some(column) = table_header_at(app, *area, mouse.column, mouse.row) {
+                if let Some(sort) = TABLE_SORT_COLUMNS.get(column).copied().flatten() {
+                    app.sort_table_by(sort);
+                }
+            } else if let Some(idx) = table_row_at(app, *area, mouse.row) {
+                let rows = app.table_rows();
+                let clicked = (rows[idx].venue.clone(), rows[idx].symbol.clone());
+                // A second click on the selected row opens it.
+                if app.state.table.selected.as_ref() == Some(&clicked) {
+                    app.open_detail();
+                } else {
+                    app.select_instrument(&clicked.0, &clicked.1);
+                }
+            }
+        }
+        ("table", delta) => app.move_table_selection(delta),
+        ("heatmap", 0) => {
+            app.focus_pane(pane);
+            let heatmap = app.heatmap();
+            if let Some((row, column)) = heatmap_cell_at(&heatmap, *area, mouse.column, mouse.row) {
+                if app.state.heatmap.cursor_position(&heatmap) == Some((row, column)) {
+                    app.open_heatmap_cell();
+                } else {
+                    app.state.heatmap.cursor =
+                        Some((heatmap.assets[row].clone(), heatmap.venues[column].clone()));
+                }
+            }
+        }
+        ("heatmap", delta) => app.move_heatmap_cursor(delta, 0),
+        ("alerts", delta) if delta != 0 => {
+            let visible = area.height.saturating_sub(2);
+            let max = (alert_panel_lines(app).len() as u16).saturating_sub(visible);
+            app.state.alerts_scroll = app
+                .state
+                .alerts_scroll
+                .saturating_add_signed(delta as i16)
+                .min(max);
+        }
+        ("log", delta) if delta != 0 => app.scroll_log(delta),
+        (pane, 0) => app.focus_pane(pane),
+        _ => {}
+    }
+}
+
+fn handle_picker_key(app: &mut QuantumDesk, code: KeyCode) {
+    let Some(picker) = app.state.picker.as_mut() else {
+        return;
+    };
+    match code {
+        KeyCode::Esc => app.close_symbol_picker(),
+        KeyCode::Enter => app.confirm_symbol_picker(),
+        KeyCode::Up => picker.move_selection(-1),
+        KeyCode::Down => picker.move_selection(1),
+        KeyCode::Backspace => {
+            picker.query.pop();
+            app.refresh_picker();
+        }
+        KeyCode::Char(ch) => {
+            picker.query.push(ch);
+            app.refresh_picker();
+        }
+        _ => {}
+    }
+}
+
+fn handle_palette_key(app: &mut QuantumDesk, key: KeyEvent) {
+    let Some(palette) = app.state.palette.as_mut() else {
+        return;
+    };
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => app.close_palette(),
+        KeyCode::Enter => app.confirm_palette(),
+        KeyCode::Up => palette.move_selection(-1),
+        KeyCode::Down => palette.move_selection(1),
+        KeyCode::Char('p') if control => palette.move_selection(-1),
+        KeyCode::Char('n') if control => palette.move_selection(1),
+        KeyCode::Backspace => {
+            palette.query.pop();
+            app.refresh_palette();
+        }
+        KeyCode::Char(ch) => {
+            palette.query.push(ch);
+            app.refresh_palette();
+        }
+        _ => {}
+    }
+}
+
+fn handle_filter_key(app: &mut QuantumDesk, code: KeyCode) {
+    let table = &mut app.state.table;
+    match code {
+        KeyCode::Esc => {
+            table.filter.clear();
+            table.editing_filter = false;
+        }
+        KeyCode::Enter => table.editing_filter = false,
+        KeyCode::Backspace => {
+            table.filter.pop();
+        }
+        KeyCode::Char(ch) => table.filter.push(ch),
+        _ => {}
+    }
+}
+
+/// A widget that workspace layouts can place by name.
+pub trait Pane {
+    /// Preferred height in rows, borders included, for `auto` sizing. `None`
+    /// takes whatever the layout gives; `Some(0)` hides the pane.
+    fn height(&self, _app: &QuantumDesk) -> Option<u16> {
+        None
+    }
+
+    /// Whether the pane still shows in compact mode.
+    fn in_compact(&self) -> bool {
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &QuantumDesk);
+}
+
+/// Built-in pane backed by plain functions.
+struct FnPane {
+    height: fn(&QuantumDesk) -> Option<u16>,
+    draw: fn(&mut Frame, Rect, &QuantumDesk),
+    in_compact: bool,
+}
+
+impl Pane for FnPane {
+    fn height(&self, app: &QuantumDesk) -> Option<u16> {
+        (self.height)(app)
+    }
+
+    fn in_compact(&self) -> bool {
+        self.in_compact
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+        (self.draw)(frame, area, app)
+    }
+}
+
+/// Pane types by the name layouts refer to them with.
+pub struct PaneRegistry {
+    panes: HashMap<String, Box<dyn Pane>>,
+}
+
+impl Default for PaneRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            panes: HashMap::new(),
+        };
+        let fill = |_: &QuantumDesk| None;
+        let builtin = |height, draw, in_compact| FnPane {
+            height,
+            draw,
+            in_compact,
+        };
+        registry.register(
+            "header",
+            builtin(
+                |app| Some(header_lines(app).len() as u16 + 2),
+                draw_header,
+                true,
+            ),
+        );
+        registry.register("table", builtin(fill, draw_funding_table, true));
+        registry.register(
+            "term_structure",
+            builtin(
+                |app| curve_asset(app).map_or(Some(0), |_| None),
+                |frame, area, app| {
+                    if let Some(asset) = curve_asset(app) {
+                        draw_term_structure(frame, area, app.theme(), asset);
+                    }
+                },
+                false,
+            ),
+        );
+        registry.register(
+            "opportunities",
+            builtin(
+                |app| match app.state.opportunities.len().min(MAX_OPPORTUNITY_ROWS) {
+                    0 => Some(0),
+                    rows => Some(rows as u16 + 3),
+                },
+                draw_opportunities,
+                false,
+            ),
+        );
+        registry.register(
+            "vol_surface",
+            builtin(
+                |app| match vol_asset(app).and_then(|asset| asset.vol.as_ref()) {
+                    Some(vol) if !vol.expiries.is_empty() => {
+                        Some(vol.expiries.len().min(MAX_VOL_ROWS) as u16 + 3)
+                    }
+                    _ => Some(0),
+                },
+                |frame, area, app| {
+                    draw_vol_surface(
+                        frame,
+                        area,
+                        app.theme(),
+                        &app.state.metrics_summary,
+                        vol_asset(app),
+                    )
+                },
+                false,
+            ),
+        );
+        registry.register(
+            "alerts",
+            builtin(
+                |app| Some(alert_panel_lines(app).len() as u16 + 2),
+                draw_alerts,
+                false,
+            ),
+        );
+        registry.register("log", builtin(|_| Some(LOG_ROWS + 2), draw_log, false));
+        registry.register(
+            "heatmap",
+            builtin(
+                |app| Some(app.state.metrics_summary.assets.len() as u16 + 3),
+                draw_heatmap,
+                true,
+            ),
+        );
+        registry.register(
+            "health",
+            builtin(
+                fill,
+                |frame, area, app| {
+                    draw_feed_health(frame, area, app.theme(), &app.state.feed_health)
+                },
+                true,
+            ),
+        );
+        registry
+    }
+}
+
+impl PaneRegistry {
+    /// Adds or replaces the pane type layouts refer to as `name`.
+    pub fn register(&mut self, name: &str, pane: impl Pane + 'static) {
+        self.panes.insert(name.to_string(), Box::new(pane));
+    }
+
+    fn height(&self, name: &str, app: &QuantumDesk) -> Option<u16> {
+        match self.panes.get(name) {
+            Some(pane) if app.is_compact() && !pane.in_compact() => Some(0),
+            Some(pane) => pane.height(app),
+            None => Some(3),
+        }
+    }
+
+    /// Draws the named pane; unknown names show as a placeholder so a typo
+    /// in the config is visible.
+    fn draw(&self, name: &str, frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+        match self.panes.get(name) {
+            Some(pane) => pane.draw(frame, area, app),
+            None => frame.render_widget(
+                Paragraph::new(format!("No pane type named {:?}", name)).block(app.theme().block()),
+                area,
+            ),
+        }
+    }
+}
+
+/// The selected row's asset when `has_data`, else the first asset that is.
+fn focused_asset(
+    app: &QuantumDesk,
+    has_data: impl Fn(&AssetMetrics) -> bool,
+) -> Option<&AssetMetrics> {
+    let assets = &app.state.metrics_summary.assets;
+    let selected = app.selected_snapshot().map(|snapshot| &snapshot.asset);
+    assets
+        .iter()
+        .find(|asset| Some(&asset.asset) == selected && has_data(asset))
+        .or_else(|| assets.iter().find(|asset| has_data(asset)))
+}
+
+/// Asset whose futures curve the curve pane shows.
+fn curve_asset(app: &QuantumDesk) -> Option<&AssetMetrics> {
+    focused_asset(app, |asset| !asset.term_structure.is_empty())
+}
+
+/// Asset whose options surface the vol pane shows.
+fn vol_asset(app: &QuantumDesk) -> Option<&AssetMetrics> {
+    focused_asset(app, |asset| asset.vol.is_some())
+}
+
+/// Draws the active workspace and overlays; returns where each pane went.
+fn draw(frame: &mut Frame, app: &QuantumDesk, panes: &PaneRegistry) -> Vec<(String, Rect)> {
     let size = frame.size();
-    let mut show_alerts_panel = !app.is_compact();
-    let margin = if app.is_compact() { 0 } else { 1 } as u16;
+    let margin = if app.is_compact() { 0 } else { 1 };
+    let area = size.inner(&Margin::new(margin, margin));
+    let height = |name: &str| panes.height(name, app);
+    let placed = layout::place(&app.workspace().layout, area, &height);
+    for (name, rect) in &placed {
+        panes.draw(name, frame, *rect, app);
+    }
 
-    let metrics = &app.state.metrics_summary;
-    let header_text = format!(
-        "Venues online: {} | Avg funding: {:+.2} bps",
-        metrics.venues_online,
-        metrics.average_funding_rate * 10_000.0
-    );
+    if app.state.show_detail
+        && let Some(snapshot) = app.selected_snapshot()
+    {
+        // The detail view covers everything below the header.
+        let top = placed
+            .iter()
+            .find(|(name, _)| name == "header")
+            .map_or(area.y, |(_, rect)| rect.y + rect.height);
+        let body = Rect::new(area.x, top, area.width, area.y + area.height - top);
+        draw_instrument_detail(frame, body, app, snapshot);
+    }
 
-    let mut header_lines = vec![
-        Line::styled(header_text, Style::default().fg(Color::Cyan)),
-        Line::styled(
-            app.state.status_line.clone(),
-            Style::default().fg(Color::Gray),
-        ),
-    ];
-    if app.is_compact() {
-        header_lines.push(Line::from("Press 'q' or Esc to exit"));
+    if let Some(picker) = &app.state.picker {
+        draw_symbol_picker(frame, size, app.theme(), picker);
+    }
+    if let Some(palette) = &app.state.palette {
+        draw_command_palette(frame, size, app.theme(), palette);
     }
+    if app.state.show_help {
+        draw_help(frame, size, app);
+    }
+    placed
+}
+
+/// Keys fixed by the text inputs, listed after the configurable bindings.
+const TEXT_INPUT_KEYS: [(&str, &[(&str, &str)]); 3] = [
+    (
+        "Command palette",
+        &[
+            ("enter", "Run command"),
+            ("esc", "Close"),
+            ("up/down, ctrl-p/ctrl-n", "Move"),
+        ],
+    ),
+    (
+        "Instrument picker",
+        &[
+            ("enter", "Add instrument"),
+            ("esc", "Close"),
+            ("up/down", "Move"),
+        ],
+    ),
+    (
+        "Filter",
+        &[("enter", "Keep filter"), ("esc", "Clear filter")],
+    ),
+];
 
-    let header_height = header_lines.len() as u16 + 2;
+/// Current bindings grouped by context; any key closes it.
+fn draw_help(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let heading =
+        |title: String| Line::styled(title, Style::default().add_modifier(Modifier::BOLD));
+    let entry = |keys: String, description: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<24}", keys), theme.header()),
+            Span::raw(description),
+        ])
+    };
+    let join = |keys: &[KeyBinding]| {
+        keys.iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
-    let mut constraints = vec![Constraint::Length(header_height), Constraint::Min(5)];
-    if show_alerts_panel {
-        let mut base_lines = 2 + app.state.alerts.len() as u16;
-        if !app.state.warnings.is_empty() {
-            base_lines += 1 + app.state.warnings.len() as u16;
+    let mut lines = Vec::new();
+    for (context, bindings) in app.keymap().grouped() {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
         }
-        base_lines += 1; // exit instructions
-        let mut alerts_height = base_lines + 2; // include block borders
-        let max_height = size.height.saturating_sub(header_height.saturating_add(5));
-        if max_height < 3 {
-            show_alerts_panel = false;
-        } else {
-            alerts_height = alerts_height.clamp(3, max_height);
-            constraints.push(Constraint::Length(alerts_height));
+        lines.push(heading(context.to_string()));
+        // Workspace keys share one line rather than nine.
+        let workspaces: Vec<String> = bindings
+            .iter()
+            .filter(|(action, _)| matches!(action, Action::Workspace(_)))
+            .map(|(_, keys)| join(keys))
+            .collect();
+        for (action, keys) in &bindings {
+            match action {
+                Action::Workspace(0) => {
+                    lines.push(entry(workspaces.join("/"), "Switch workspace".to_string()))
+                }
+                Action::Workspace(_) => {}
+                _ => lines.push(entry(join(keys), action.description())),
+            }
         }
     }
+    for (context, keys) in TEXT_INPUT_KEYS {
+        lines.push(Line::from(""));
+        lines.push(heading(context.to_string()));
+        lines.extend(
+            keys.iter()
+                .map(|(keys, description)| entry(keys.to_string(), description.to_string())),
+        );
+    }
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(margin)
-        .constraints(constraints)
-        .split(size);
-
-    let header = Paragraph::new(header_lines).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(format!("QuantumDesk v{}", env!("CARGO_PKG_VERSION"))),
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = 64.min(area.width);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
     );
-    frame.render_widget(header, chunks[0]);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(theme.block().title("Key bindings (any key closes)")),
+        popup,
+    );
+}
 
+fn header_lines(app: &QuantumDesk) -> Vec<Line<'static>> {
+    let theme = app.theme();
+    let metrics = &app.state.metrics_summary;
+    let format_bps = |rate: Option<f64>| {
+        rate.map(|rate| format!("{:+.2}", rate * 10_000.0))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let mut header_lines = vec![Line::styled(
+        format!(
+            "Venues online: {} | Assets: {}",
+            metrics.venues_online,
+            metrics
+                .assets
+                .iter()
+                .map(|asset| asset.asset.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        theme.header(),
+    )];
+    for asset in metrics.assets.iter().filter(|asset| asset.perps_online > 0) {
+        let mut text = format!(
+            "{} avg funding: {:+.2} bps ({} perps) | OI-wt: {} | Vol-wt: {}",
+            asset.asset,
+            asset.average_funding_rate * 10_000.0,
+            asset.perps_online,
+            format_bps(asset.oi_weighted_funding_rate),
+            format_bps(asset.volume_weighted_funding_rate),
+        );
+        if let Some(spread) = asset
+            .spreads
+            .iter()
+            .max_by(|a, b| a.spread_bps.abs().total_cmp(&b.spread_bps.abs()))
+        {
+            text.push_str(&format!(
+                " | Widest spread: {}/{} {:+.1} bps",
+                spread.base_venue, spread.quote_venue, spread.spread_bps
+            ));
+        }
+        if !asset.open_interest_shares.is_empty() {
+            let shares = asset
+                .open_interest_shares
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{} {:.0}% (${:.0}M)",
+                        entry.venue,
+                        entry.share * 100.0,
+                        entry.open_interest / 1_000_000.0
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            text.push_str(&format!(" | OI share: {}", shares));
+        }
+        header_lines.push(Line::styled(text, theme.header()));
+    }
+    if let Some(line) = realized_line(metrics) {
+        header_lines.push(Line::styled(line, theme.header()));
+    }
+    if !app.state.etf_valuations.is_empty() {
+        let etfs = app
+            .state
+            .etf_valuations
+            .iter()
+            .map(|etf| {
+                format!(
+                    "{} {:+.1} bps (NAV ${:.2}, {:.0}k BTC)",
+                    etf.ticker,
+                    etf.premium_bps,
+                    etf.nav_per_share,
+                    etf.btc_holdings / 1_000.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        header_lines.push(Line::styled(
+            format!("ETF prem/disc: {}", etfs),
+            theme.header(),
+        ));
+    }
+    header_lines.push(Line::styled(app.state.status_line.clone(), theme.muted()));
+
+    header_lines
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let tabs = app
+        .workspaces()
+        .iter()
+        .enumerate()
+        .map(|(index, workspace)| {
+            if index == app.state.workspace {
+                format!("[{} {}]", index + 1, workspace.name)
+            } else {
+                format!("{} {}", index + 1, workspace.name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut title = vec![Span::raw(format!(
+        "QuantumDesk v{} · {} · '{}' keys",
+        env!("CARGO_PKG_VERSION"),
+        tabs,
+        app.keymap().hint(Action::Help)
+    ))];
+    if app.state.paused_at.is_some() {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(
+            format!(
+                " PAUSED · {} pending ('{}' resumes) ",
+                app.state.pending_updates,
+                app.keymap().hint(Action::Pause)
+            ),
+            theme.warning().add_modifier(Modifier::REVERSED),
+        ));
+    }
+    let header = Paragraph::new(header_lines(app)).block(theme.block().title(Line::from(title)));
+    frame.render_widget(header, area);
+}
+
+fn draw_funding_table(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let metrics = &app.state.metrics_summary;
     let format_rate = |rate: f64| format!("{:.2}%", rate * 100.0);
-    let table_rows = app
-        .state
-        .market_snapshots
+    let stats_window = app.state.stats_window;
+    let view = &app.state.table;
+    let rows = app.table_rows();
+    // Sorted by asset, rows are grouped and the asset label shows once per group.
+    let grouped = view.sort == SortColumn::Asset;
+    let gaps = table_row_gaps(&rows, grouped);
+    let table_rows = rows
         .iter()
-        .map(|snapshot| {
+        .enumerate()
+        .map(|(idx, snapshot)| {
+            let starts_group = !grouped || idx == 0 || rows[idx - 1].asset != snapshot.asset;
             let price = snapshot.perp_price.unwrap_or(snapshot.spot_price);
             let instrument = format!("{}:{}", snapshot.instrument_label, snapshot.symbol);
             let current_rate = format_rate(snapshot.funding_rate);
             let predicted_rate = snapshot
                 .predicted_funding_rate
-                .map(|rate| format_rate(rate))
+                .map(format_rate)
                 .unwrap_or_else(|| "-".to_string());
             let funding_display = format!("{} / {}", current_rate, predicted_rate);
+            let model_display = app
+                .state
+                .forecast(&snapshot.venue, &snapshot.symbol)
+                .map(|forecast| match forecast.mean_abs_error {
+                    Some(error) => format!(
+                        "{} ±{:.1}bp",
+                        format_rate(forecast.estimate),
+                        error * 10_000.0
+                    ),
+                    None => format_rate(forecast.estimate),
+                })
+                .unwrap_or_else(|| "-".to_string());
+            let instrument_metrics = metrics.instrument(&snapshot.venue, &snapshot.symbol);
+            let basis_display = if snapshot.kind == InstrumentKind::Etf {
+                instrument_metrics
+                    .and_then(|row| row.premium_bps)
+                    .map(|bps| format!("{:+.1} prem", bps))
+            } else {
+                instrument_metrics
+                    .and_then(|row| row.basis_bps.zip(row.annualized_basis))
+                    .map(|(bps, annualized)| format!("{:+.1} / {}", bps, format_rate(annualized)))
+            }
+            .unwrap_or_else(|| "-".to_string());
 
-            Row::new(vec![
+            let mut cells = vec![
+                Cell::from(if starts_group {
+                    snapshot.asset.clone()
+                } else {
+                    String::new()
+                })
+                .style(Style::default().add_modifier(Modifier::BOLD)),
                 Cell::from(snapshot.venue.clone()),
                 Cell::from(instrument),
                 Cell::from(format!("{:.2}", price)),
-                Cell::from(funding_display),
+                Cell::from(funding_display).style(theme.signed(snapshot.funding_rate)),
+                Cell::from(model_display),
+                Cell::from(basis_display),
                 Cell::from(
                     snapshot
                         .next_funding_time
                         .map(|ts| ts.format("%H:%M UTC").to_string())
                         .unwrap_or_else(|| "-".to_string()),
                 ),
-                Cell::from(snapshot.last_updated.format("%H:%M:%S").to_string()),
-            ])
+                // Cached rows show how old they are rather than a time that
+                // reads like a fresh fetch.
+                if served_stale(app, snapshot) {
+                    Cell::from(format!(
+                        "{} ago",
+                        format_age(Utc::now() - snapshot.last_updated)
+                    ))
+                    .style(theme.stale())
+                } else {
+                    Cell::from(snapshot.last_updated.format("%H:%M:%S").to_string())
+                },
+            ];
+            if let Some(window) = stats_window {
+                let stats = instrument_metrics.and_then(|row| row.stats(window));
+                let stat_cell = |render: &dyn Fn(&FundingStats) -> Option<String>| {
+                    Cell::from(stats.and_then(render).unwrap_or_else(|| "-".to_string()))
+                };
+                cells.extend([
+                    stat_cell(&|stats| {
+                        Some(format!(
+                            "{:+.2} / {:+.2}",
+                            stats.mean * 10_000.0,
+                            stats.ewma * 10_000.0
+                        ))
+                    }),
+                    stat_cell(&|stats| Some(format!("{:.2}", stats.std_dev * 10_000.0))),
+                    stat_cell(&|stats| stats.z_score.map(|z| format!("{:+.2}", z))),
+                    stat_cell(&|stats| Some(format!("{:.0}%", stats.percentile * 100.0))),
+                ]);
+            }
+            Row::new(cells).bottom_margin(gaps[idx])
         })
         .collect::<Vec<_>>();
 
-    let widths = [
+    let widths = table_widths(stats_window);
+    let mut headers = vec![
+        "Asset".to_string(),
+        "Venue".to_string(),
+        "Instrument".to_string(),
+        "Price".to_string(),
+        "Funding (APY)".to_string(),
+        "Model ±MAE".to_string(),
+        "Basis bps / Ann".to_string(),
+        "Next".to_string(),
+        "Updated".to_string(),
+    ];
+    if let Some(window) = stats_window {
+        headers.extend([
+            format!("Mean/EWMA {}", window),
+            "σ bps".to_string(),
+            "z".to_string(),
+            "Pctl".to_string(),
+        ]);
+    }
+
+    let arrow = if view.descending { " ▼" } else { " ▲" };
+    let sort_column = match view.sort {
+        SortColumn::Asset => 0,
+        SortColumn::Venue => 1,
+        SortColumn::Instrument => 2,
+        SortColumn::Funding => 4,
+        SortColumn::Basis | SortColumn::Annualized => 6,
+        SortColumn::NextFunding => 7,
+    };
+    headers[sort_column].push_str(arrow);
+
+    let mut title = format!("Funding Monitor · sort {}{}", view.sort.label(), arrow);
+    if view.editing_filter {
+        title.push_str(&format!(" · filter: {}_", view.filter));
+    } else if !view.filter.is_empty() {
+        title.push_str(&format!(" · filter: {} ({} rows)", view.filter, rows.len()));
+    }
+    let table = Table::new(table_rows, widths)
+        .header(Row::new(headers).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(focus_block(app, "table").title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .column_spacing(1);
+    let selected = view.selected_index(&rows);
+    let offset = table_offset(&gaps, selected, area.height.saturating_sub(3));
+    let mut table_state = TableState::default()
+        .with_offset(offset)
+        .with_selected(selected);
+    frame.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Sort each Funding Monitor column applies when its header is clicked.
+const TABLE_SORT_COLUMNS: [Option<SortColumn>; 9] = [
+    Some(SortColumn::Asset),
+    Some(SortColumn::Venue),
+    Some(SortColumn::Instrument),
+    None,
+    Some(SortColumn::Funding),
+    None,
+    Some(SortColumn::Basis),
+    Some(SortColumn::NextFunding),
+    None,
+];
+
+fn table_widths(stats_window: Option<StatsWindow>) -> Vec<Constraint> {
+    let mut widths = vec![
+        Constraint::Length(6),
         Constraint::Length(10),
         Constraint::Length(20),
         Constraint::Length(14),
         Constraint::Length(18),
+        Constraint::Length(16),
+        Constraint::Length(18),
         Constraint::Length(12),
         Constraint::Length(12),
     ];
+    if stats_window.is_some() {
+        widths.extend([
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(6),
+        ]);
+    }
+    widths
+}
 
-    let table = Table::new(table_rows, widths)
+/// Blank lines after each row: one between asset groups when grouped.
+fn table_row_gaps(rows: &[&MarketSnapshot], grouped: bool) -> Vec<u16> {
+    (0..rows.len())
+        .map(|idx| {
+            let ends_group = rows
+                .get(idx + 1)
+                .is_some_and(|next| next.asset != rows[idx].asset);
+            u16::from(grouped && ends_group)
+        })
+        .collect()
+}
+
+/// First row shown: the least scrolling that keeps the selection within
+/// `height` lines. Drawing and mouse hit-testing both go through this.
+fn table_offset(gaps: &[u16], selected: Option<usize>, height: u16) -> usize {
+    let Some(selected) = selected else {
+        return 0;
+    };
+    let used = |from: usize| -> u16 { gaps[from..=selected].iter().map(|gap| 1 + gap).sum() };
+    let mut offset = 0;
+    while offset < selected && used(offset) > height {
+        offset += 1;
+    }
+    offset
+}
+
+/// Funding Monitor row drawn at screen row `y`, if any.
+fn table_row_at(app: &QuantumDesk, area: Rect, y: u16) -> Option<usize> {
+    // Border and header row above the body, border below.
+    let top = area.y + 2;
+    let bottom = (area.y + area.height).saturating_sub(1);
+    let rows = app.table_rows();
+    let gaps = table_row_gaps(&rows, app.state.table.sort == SortColumn::Asset);
+    let selected = app.state.table.selected_index(&rows);
+    let mut line = top;
+    for (idx, gap) in
+        gaps.iter()
+            .enumerate()
+            .skip(table_offset(&gaps, selected, bottom.saturating_sub(top)))
+    {
+        if line >= bottom {
+            break;
+        }
+        if line == y {
+            return Some(idx);
+        }
+        line += 1 + gap;
+    }
+    None
+}
+
+/// Funding Monitor column whose header is at (`x`, `y`), if any.
+fn table_header_at(app: &QuantumDesk, area: Rect, x: u16, y: u16) -> Option<usize> {
+    if y != area.y + 1 {
+        return None;
+    }
+    let inner = area.inner(&Margin::new(1, 1));
+    Layout::horizontal(table_widths(app.state.stats_window))
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(Rect::new(inner.x, y, inner.width, 1))
+        .iter()
+        .position(|column| (column.x..column.x + column.width).contains(&x))
+}
+
+/// Panel block, with the focus border when `pane` has keyboard focus.
+fn focus_block<'a>(app: &QuantumDesk, pane: &str) -> Block<'a> {
+    let theme = app.theme();
+    if app.is_focused(pane) {
+        theme.block().border_style(theme.focused())
+    } else {
+        theme.block()
+    }
+}
+
+/// Assets by venues, colored on a diverging scale around zero that spans the
+/// largest absolute value shown.
+fn draw_heatmap(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let keymap = app.keymap();
+    let heatmap = app.heatmap();
+    let metric = app.state.heatmap.metric;
+    let scale = heatmap
+        .cells
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|cell| cell.value.abs())
+        .fold(0.0_f64, f64::max);
+    let cursor = app.state.heatmap.cursor_position(&heatmap);
+    let focused = app.focused_pane() == "heatmap";
+    let rows = heatmap
+        .assets
+        .iter()
+        .zip(&heatmap.cells)
+        .enumerate()
+        .map(|(row, (asset, cells))| {
+            let mut line = vec![
+                Cell::from(asset.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            line.extend(cells.iter().enumerate().map(|(column, cell)| {
+                let style = if focused && cursor == Some((row, column)) {
+                    theme.selected()
+                } else {
+                    match cell {
+                        Some(cell) if scale > 0.0 => theme.diverging(cell.value / scale),
+                        _ => theme.muted(),
+                    }
+                };
+                Cell::from(
+                    cell.as_ref()
+                        .map(|cell| format!("{:+.2}%", cell.value * 100.0))
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(style)
+            }));
+            Row::new(line)
+        })
+        .collect::<Vec<_>>();
+
+    let widths = heatmap_widths(&heatmap);
+    let mut headers = vec!["Asset".to_string()];
+    headers.extend(heatmap.venues.iter().cloned());
+    let table = Table::new(rows, widths)
+        .header(Row::new(headers).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(focus_block(app, "heatmap").title(format!(
+            "Heatmap · {} · scale ±{:.2}% ('{}' switches, '{}' focuses, '{}' opens)",
+            metric.label(),
+            scale * 100.0,
+            keymap.hint(Action::HeatmapMetric),
+            keymap.hint(Action::FocusNext),
+            keymap.hint(Action::Open)
+        )))
+        .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn heatmap_widths(heatmap: &Heatmap) -> Vec<Constraint> {
+    let mut widths = vec![Constraint::Length(6)];
+    widths.extend(heatmap.venues.iter().map(|_| Constraint::Length(12)));
+    widths
+}
+
+/// Heatmap (row, column) drawn at (`x`, `y`), if any.
+fn heatmap_cell_at(heatmap: &Heatmap, area: Rect, x: u16, y: u16) -> Option<(usize, usize)> {
+    let row = usize::from(y.checked_sub(area.y + 2)?);
+    let inner = area.inner(&Margin::new(1, 1));
+    let column = Layout::horizontal(heatmap_widths(heatmap))
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(Rect::new(inner.x, y, inner.width, 1))
+        .iter()
+        .position(|column| (column.x..column.x + column.width).contains(&x))?
+        // The first column holds the asset names.
+        .checked_sub(1)?;
+    (row < heatmap.assets.len()).then_some((row, column))
+}
+
+/// Whether the row's feed is showing cached data from before its last failed
+/// or held-back fetch.
+fn served_stale(app: &QuantumDesk, snapshot: &MarketSnapshot) -> bool {
+    app.state.feed_health.iter().any(|feed| {
+        feed.venue == snapshot.venue
+            && feed.subject == snapshot.symbol
+            && feed.source == Some(FeedSource::StaleCache)
+    })
+}
+
+fn alert_panel_lines(app: &QuantumDesk) -> Vec<Line<'static>> {
+    let theme = app.theme();
+    let mut alert_lines = vec![Line::styled(
+        "Alerts".to_string(),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    alert_lines.extend(app.state.alerts.iter().map(|alert| {
+        let status_style = if alert.needs_attention() {
+            theme.alert_triggered()
+        } else {
+            theme.muted()
+        };
+        let mut text = format!("• {} | Threshold {}", alert.name, alert.threshold);
+        if let Some(at) = alert.last_triggered.filter(|_| alert.is_triggered) {
+            text.push_str(&format!(" | Triggered {}", at.format("%H:%M:%S")));
+            if alert.acknowledged {
+                text.push_str(" (acked)");
+            }
+        }
+        if alert.muted {
+            text.push_str(" | muted");
+        }
+        Line::styled(text, status_style)
+    }));
+
+    alert_lines
+}
+
+fn draw_alerts(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let alerts = Paragraph::new(alert_panel_lines(app))
+        .block(theme.block().title("Alerts"))
+        .scroll((app.state.alerts_scroll, 0));
+    frame.render_widget(alerts, area);
+}
+
+/// Log pane height; wrapped error chains need the room.
+const LOG_ROWS: u16 = 8;
+/// Indent of a log message's continuation lines, past the time stamp.
+const LOG_INDENT: usize = 9;
+
+/// Newest events at the bottom, each wrapped to the pane width so error
+/// chains are shown whole; scrolled back by `log_scroll` events.
+fn draw_log(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let keymap = app.keymap();
+    let filter = app.state.log_filter;
+    let inner = area.inner(&Margin::new(1, 1));
+    let width = usize::from(inner.width).max(1);
+    let shown = app.state.events.filtered(filter).count();
+    let mut lines: Vec<Line> = Vec::new();
+    for event in app
+        .state
+        .events
+        .filtered(filter)
+        .rev()
+        .skip(app.state.log_scroll)
+    {
+        let mut text = format!(
+            "{} {:<5} {:<7} {}",
+            event.at.format("%H:%M:%S"),
+            event.severity,
+            event.kind,
+            event.message
+        );
+        if event.repeats > 0 {
+            text.push_str(&format!(" (×{})", event.repeats + 1));
+        }
+        let style = match event.severity {
+            Severity::Error => theme.negative(),
+            Severity::Warn => theme.warning(),
+            Severity::Info => Style::default(),
+        };
+        let mut wrapped: Vec<Line> = wrap_chars(&text, width, LOG_INDENT)
+            .into_iter()
+            .map(|line| Line::styled(line, style))
+            .collect();
+        wrapped.append(&mut lines);
+        lines = wrapped;
+        if lines.len() >= usize::from(inner.height) {
+            break;
+        }
+    }
+    // The oldest event shown may be cut at the top, the newest never is.
+    let overflow = lines.len().saturating_sub(usize::from(inner.height));
+    lines.drain(..overflow);
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            format!("No {} events or worse", filter),
+            theme.muted(),
+        ));
+    }
+
+    let mut title = format!("Log · {}+ · {} events", filter, shown);
+    if app.state.log_scroll > 0 {
+        title.push_str(&format!(" · {} newer below", app.state.log_scroll));
+    }
+    title.push_str(&format!(
+        " ('{}' filters, '{}' focuses)",
+        keymap.hint(Action::LogSeverity),
+        keymap.hint(Action::FocusNext)
+    ));
+    let log = Paragraph::new(lines).block(focus_block(app, "log").title(title));
+    frame.render_widget(log, area);
+}
+
+/// Splits `text` at its line breaks and into lines of at most `width`
+/// characters, continuation lines indented by `indent`.
+fn wrap_chars(text: &str, width: usize, indent: usize) -> Vec<String> {
+    let indent = indent.min(width.saturating_sub(1));
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut chars = paragraph.chars().peekable();
+        loop {
+            let prefix = if lines.is_empty() { 0 } else { indent };
+            let mut line = " ".repeat(prefix);
+            line.extend(chars.by_ref().take(width - prefix));
+            lines.push(line);
+            if chars.peek().is_none() {
+                break;
+            }
+        }
+    }
+    lines
+}
+
+fn draw_command_palette(frame: &mut Frame, area: Rect, theme: &Theme, palette: &CommandPalette) {
+    let height = (palette.matches.len() as u16 + 5).min(area.height);
+    let width = area.width.saturating_mul(3) / 5;
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + area.height / 5,
+        width,
+        height.min(area.height - area.height / 5),
+    );
+
+    let mut lines = Vec::new();
+    match &palette.pending {
+        Some((title, prompt)) => {
+            lines.push(Line::styled(
+                title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            lines.push(Line::styled(prompt.clone(), theme.muted()));
+            lines.push(Line::from(format!("> {}_", palette.query)));
+        }
+        None => {
+            lines.push(Line::styled(
+                format!(": {}_", palette.query),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            lines.push(Line::from(""));
+            if palette.matches.is_empty() {
+                lines.push(Line::styled("No matching commands", theme.muted()));
+            }
+            lines.extend(palette.matches.iter().enumerate().map(|(idx, title)| {
+                if idx == palette.selected {
+                    Line::styled(title.clone(), theme.selected())
+                } else {
+                    Line::from(title.clone())
+                }
+            }));
+        }
+    }
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(theme.block().title("Commands (Enter runs, Esc closes)")),
+        popup,
+    );
+}
+
+/// Averages `values` into at most `buckets` consecutive groups.
+fn downsample(values: &[f64], buckets: usize) -> Vec<f64> {
+    if buckets == 0 || values.len() <= buckets {
+        return values.to_vec();
+    }
+    values
+        .chunks(values.len().div_ceil(buckets))
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect()
+}
+
+/// Funding and basis history, price sparkline, settlement countdown and the
+/// raw snapshot for one instrument, over the stats window (1d by default).
+fn draw_instrument_detail(
+    frame: &mut Frame,
+    area: Rect,
+    app: &QuantumDesk,
+    snapshot: &MarketSnapshot,
+) {
+    let theme = app.theme();
+    let window = app.state.stats_window.unwrap_or(StatsWindow::Day);
+    let now = app.display_time();
+    let samples: Vec<&HistorySample> = app
+        .history()
+        .window_until(&snapshot.venue, &snapshot.symbol, window.duration(), now)
+        .collect();
+    let hours_ago = |at: DateTime<Utc>| (at - now).num_seconds() as f64 / 3_600.0;
+    let funding: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|sample| (hours_ago(sample.at), sample.funding_rate * 10_000.0))
+        .collect();
+    let basis: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|sample| sample.reference_price > 0.0)
+        .map(|sample| {
+            (
+                hours_ago(sample.at),
+                (sample.price / sample.reference_price - 1.0) * 10_000.0,
+            )
+        })
+        .collect();
+
+    frame.render_widget(Clear, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(8),
+            Constraint::Length(5),
+            Constraint::Length(12),
+        ])
+        .split(area);
+
+    let span_hours = window.duration().num_hours() as f64;
+    let (min_y, max_y) = funding
+        .iter()
+        .chain(&basis)
+        .map(|(_, y)| *y)
+        .fold((0.0_f64, 0.0_f64), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let pad = ((max_y - min_y) * 0.1).max(0.5);
+    let (min_y, max_y) = (min_y - pad, max_y + pad);
+    let datasets = vec![
+        Dataset::default()
+            .name("Funding bps")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(theme.series(0))
+            .data(&funding),
+        Dataset::default()
+            .name("Basis bps")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(theme.series(1))
+            .data(&basis),
+    ];
+    let chart = Chart::new(datasets)
+        .block(theme.block().title(format!(
+            "{} {} · funding & basis, last {} ('{}' changes, '{}' closes)",
+            snapshot.venue,
+            snapshot.symbol,
+            window,
+            app.keymap().hint(Action::StatsWindow),
+            app.keymap().hint(Action::Back)
+        )))
+        .x_axis(
+            Axis::default()
+                .title("Hours")
+                .bounds([-span_hours, 0.0])
+                .labels(vec![
+                    Span::raw(format!("-{:.0}", span_hours)),
+                    Span::raw(format!("-{:.0}", span_hours / 2.0)),
+                    Span::raw("now"),
+                ]),
+        )
+        .y_axis(Axis::default().bounds([min_y, max_y]).labels(vec![
+            Span::raw(format!("{:.1}", min_y)),
+            Span::raw(format!("{:.1}", (min_y + max_y) / 2.0)),
+            Span::raw(format!("{:.1}", max_y)),
+        ]));
+    frame.render_widget(chart, rows[0]);
+
+    // One bar per column across the whole window, so the latest prices are
+    // always on screen; the range shown is that of the plotted bars.
+    let prices: Vec<f64> = samples.iter().map(|sample| sample.price).collect();
+    let prices = downsample(&prices, rows[1].width.saturating_sub(2) as usize);
+    let low = prices.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = prices.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // Sparkline bars are integers; scale the range to a fixed resolution.
+    let bars: Vec<u64> = prices
+        .iter()
+        .map(|price| {
+            if high > low {
+                ((price - low) / (high - low) * 100.0).round() as u64 + 1
+            } else {
+                1
+            }
+        })
+        .collect();
+    let price_title = if prices.is_empty() {
+        "Price (no history yet)".to_string()
+    } else {
+        format!("Price {:.2} – {:.2}", low, high)
+    };
+    frame.render_widget(
+        Sparkline::default()
+            .block(theme.block().title(price_title))
+            .style(theme.series(3))
+            .data(&bars),
+        rows[1],
+    );
+
+    let lower = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[2]);
+
+    let format_rate = |rate: Option<f64>| {
+        rate.map(|rate| format!("{:+.4}% ({:+.2} bps)", rate * 100.0, rate * 10_000.0))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let countdown = snapshot
+        .next_funding_time
+        .map(|at| {
+            let secs = (at - now).num_seconds().max(0);
+            format!(
+                "{} ({:02}h {:02}m {:02}s)",
+                at.format("%H:%M UTC"),
+                secs / 3_600,
+                secs / 60 % 60,
+                secs % 60
+            )
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let forecast = app.state.forecast(&snapshot.venue, &snapshot.symbol);
+    let realized = app
+        .state
+        .metrics_summary
+        .instrument(&snapshot.venue, &snapshot.symbol)
+        .and_then(|row| row.stats(window));
+    let funding_lines = vec![
+        Line::from(format!("Next settlement   {}", countdown)),
+        Line::from(format!(
+            "Current funding   {}",
+            format_rate(snapshot.is_perp().then_some(snapshot.funding_rate))
+        )),
+        Line::from(format!(
+            "Venue predicted   {}",
+            format_rate(snapshot.predicted_funding_rate)
+        )),
+        Line::from(format!(
+            "Model estimate    {}",
+            format_rate(forecast.map(|forecast| forecast.estimate))
+        )),
+        Line::from(format!(
+            "Model MAE         {}",
+            forecast
+                .and_then(|forecast| forecast.mean_abs_error.map(|error| (error, forecast)))
+                .map(|(error, forecast)| format!(
+                    "{:.2} bps over {} settlements",
+                    error * 10_000.0,
+                    forecast.scored_settlements
+                ))
+                .unwrap_or_else(|| "-".to_string())
+        )),
+        Line::from(format!(
+            "Realized {:<3} mean {}",
+            window,
+            format_rate(realized.map(|stats| stats.mean))
+        )),
+        Line::from(format!(
+            "Realized {:<3} σ    {}",
+            window,
+            realized
+                .map(|stats| format!(
+                    "{:.2} bps ({} samples)",
+                    stats.std_dev * 10_000.0,
+                    stats.samples
+                ))
+                .unwrap_or_else(|| "-".to_string())
+        )),
+    ];
+    frame.render_widget(
+        Paragraph::new(funding_lines).block(theme.block().title("Predicted vs realized funding")),
+        lower[0],
+    );
+
+    let price = |value: Option<f64>| {
+        value
+            .map(|value| format!("{:.2}", value))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let time = |value: Option<DateTime<Utc>>| {
+        value
+            .map(|at| at.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let raw_lines = vec![
+        Line::from(format!(
+            "asset {} · kind {:?} · label {}",
+            snapshot.asset, snapshot.kind, snapshot.instrument_label
+        )),
+        Line::from(format!(
+            "spot {} · perp {}",
+            price(Some(snapshot.spot_price)),
+            price(snapshot.perp_price)
+        )),
+        Line::from(format!(
+            "index {} · mark {}",
+            price(snapshot.index_price),
+            price(snapshot.mark_price)
+        )),
+        Line::from(format!(
+            "funding {} · predicted {}",
+            snapshot.funding_rate,
+            snapshot
+                .predicted_funding_rate
+                .map(|rate| rate.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        )),
+        Line::from(format!(
+            "open interest {} · 24h volume {}",
+            price(snapshot.open_interest),
+            price(snapshot.volume_24h)
+        )),
+        Line::from(format!("next funding {}", time(snapshot.next_funding_time))),
+        Line::from(format!("expiry {}", time(snapshot.expiry))),
+        Line::from(format!("updated {}", time(Some(snapshot.last_updated)))),
+    ];
+    frame.render_widget(
+        Paragraph::new(raw_lines).block(theme.block().title("Latest snapshot")),
+        lower[1],
+    );
+}
+
+fn draw_symbol_picker(frame: &mut Frame, area: Rect, theme: &Theme, picker: &SymbolPicker) {
+    let height = (picker.matches.len() as u16 + 5).min(area.height);
+    let width = area.width.saturating_mul(3) / 5;
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let mut lines = vec![
+        Line::styled(
+            format!("> {}_", picker.query),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::from(""),
+    ];
+    if picker.matches.is_empty() {
+        lines.push(Line::styled("No matching instruments", theme.muted()));
+    }
+    lines.extend(picker.matches.iter().enumerate().map(|(idx, info)| {
+        let expiry = info
+            .expiry
+            .map(|at| format!(" exp {}", at.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        let text = format!(
+            "{:<9} {:<22} {:<6} {:?}{}",
+            info.venue, info.symbol, info.asset, info.kind, expiry
+        );
+        if idx == picker.selected {
+            Line::styled(text, theme.selected())
+        } else {
+            Line::from(text)
+        }
+    }));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            theme
+                .block()
+                .title("Add instrument (Enter adds, Esc closes)"),
+        ),
+        popup,
+    );
+}
+
+fn draw_feed_health(frame: &mut Frame, area: Rect, theme: &Theme, feeds: &[FeedStats]) {
+    let now = Utc::now();
+    let format_ms = |ms: Option<f64>| {
+        ms.map(|ms| format!("{:.0}ms", ms))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let rows = feeds
+        .iter()
+        .map(|feed| {
+            let source_style = match feed.source {
+                Some(FeedSource::StaleCache) => theme.stale(),
+                Some(_) => theme.positive(),
+                None => theme.muted(),
+            };
+            let error_rate = feed.error_rate().unwrap_or(0.0);
+            let error_style = if error_rate >= 0.5 {
+                theme.negative()
+            } else if error_rate > 0.0 {
+                theme.warning()
+            } else {
+                theme.muted()
+            };
+            Row::new(vec![
+                Cell::from(feed.venue.clone()),
+                Cell::from(feed.subject.clone()),
+                Cell::from(
+                    feed.source
+                        .map(|source| source.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(source_style),
+                Cell::from(
+                    feed.last_success
+                        .map(|at| at.format("%H:%M:%S").to_string())
+                        .unwrap_or_else(|| "never".to_string()),
+                ),
+                Cell::from(format_ms(feed.latency_ms(0.5))),
+                Cell::from(format_ms(feed.latency_ms(0.99))),
+                Cell::from(
+                    feed.error_rate()
+                        .map(|rate| format!("{:.0}%", rate * 100.0))
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(error_style),
+                Cell::from(
+                    feed.cache_age(now)
+                        .map(format_age)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::from(
+                    feed.last_error
+                        .as_ref()
+                        .map(|(at, error)| format!("{} {}", at.format("%H:%M:%S"), error))
+                        .unwrap_or_default(),
+                )
+                .style(theme.warning()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(20),
+        Constraint::Length(12),
+        Constraint::Length(9),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Min(20),
+    ];
+    let table = Table::new(rows, widths)
         .header(
             Row::new(vec![
                 "Venue",
-                "Instrument",
-                "Price",
-                "Funding (APY)",
-                "Next",
-                "Updated",
+                "Feed",
+                "Source",
+                "Last OK",
+                "p50",
+                "p99",
+                "Err",
+                "Age",
+                "Last error",
             ])
             .style(Style::default().add_modifier(Modifier::BOLD)),
         )
-        .block(
-            Block::default()
-                .title("Funding Monitor")
-                .borders(Borders::ALL),
-        )
+        .block(theme.block().title("Feed Health"))
         .column_spacing(1);
-    frame.render_widget(table, chunks[1]);
+    frame.render_widget(table, area);
+}
 
-    if show_alerts_panel {
-        let mut alert_lines = vec![Line::styled(
-            "Alerts".to_string(),
-            Style::default().add_modifier(Modifier::BOLD),
-        )];
-        alert_lines.extend(app.state.alerts.iter().map(|alert| {
-            let status_color = if alert.is_triggered {
-                Color::Red
+fn format_age(age: ChronoDuration) -> String {
+    let secs = age.num_seconds().max(0);
+    match secs {
+        0..=119 => format!("{}s", secs),
+        120..=7_199 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3_600),
+    }
+}
+
+const MAX_OPPORTUNITY_ROWS: usize = 5;
+
+fn draw_opportunities(frame: &mut Frame, area: Rect, app: &QuantumDesk) {
+    let theme = app.theme();
+    let format_pct = |value: f64| format!("{:+.3}%", value * 100.0);
+    let rows = app
+        .state
+        .opportunities
+        .iter()
+        .take(MAX_OPPORTUNITY_ROWS)
+        .map(|opportunity| {
+            let net_style = if opportunity.net_carry > 0.0 {
+                theme.positive()
             } else {
-                Color::Gray
+                theme.muted()
             };
-            Line::styled(
-                format!("• {} | Threshold {}", alert.name, alert.threshold),
-                Style::default().fg(status_color),
-            )
-        }));
-
-        if !app.state.warnings.is_empty() {
-            alert_lines.push(Line::from(""));
-            alert_lines.push(Line::styled(
-                "Warnings".to_string(),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ));
-            for warning in &app.state.warnings {
-                alert_lines.push(Line::styled(
-                    format!("• {}", warning),
-                    Style::default().fg(Color::Yellow),
-                ));
-            }
+            Row::new(vec![
+                Cell::from(opportunity.kind.label()),
+                Cell::from(format!(
+                    "{}:{}",
+                    opportunity.long.venue, opportunity.long.symbol
+                )),
+                Cell::from(format!(
+                    "{}:{}",
+                    opportunity.short.venue, opportunity.short.symbol
+                )),
+                Cell::from(format_pct(opportunity.gross_carry)),
+                Cell::from(format_pct(-opportunity.fees)),
+                Cell::from(format_pct(opportunity.net_carry)).style(net_style),
+                Cell::from(format!("{:+.2}%", opportunity.annualized_net * 100.0)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(24),
+        Constraint::Length(24),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(vec![
+                "Trade", "Long", "Short", "Funding", "Fees", "Net", "Net Ann",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(theme.block().title(format!(
+            "Opportunities ({}h horizon)",
+            app.carry_horizon_hours()
+        )))
+        .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+const MAX_VOL_ROWS: usize = 6;
+
+/// Shortest-window realized vol of an asset's index series, falling back to
+/// any of its series, for comparison against implied vol.
+fn reference_realized_vol<'a>(
+    metrics: &'a MetricsSummary,
+    asset: &str,
+) -> Option<(&'a RealizedVol, f64)> {
+    let mut candidates: Vec<&RealizedVol> = metrics
+        .realized_vol
+        .iter()
+        .filter(|realized| realized.asset == asset)
+        .collect();
+    candidates.sort_by_key(|realized| realized.window_hours);
+    candidates
+        .iter()
+        .find(|realized| realized.kind == InstrumentKind::Index)
+        .or_else(|| candidates.first())
+        .and_then(|realized| {
+            realized
+                .close_to_close
+                .or(realized.parkinson)
+                .map(|annualized| (*realized, annualized))
+        })
+}
+
+fn realized_line(metrics: &MetricsSummary) -> Option<String> {
+    let mut parts = Vec::new();
+    for (realized, _) in metrics
+        .assets
+        .iter()
+        .filter_map(|asset| reference_realized_vol(metrics, &asset.asset))
+    {
+        let format_vol = |value: Option<f64>| {
+            value
+                .map(|value| format!("{:.1}%", value * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        parts.push(format!(
+            "RV {}h {}:{} c2c {} / Parkinson {}",
+            realized.window_hours,
+            realized.venue,
+            realized.symbol,
+            format_vol(realized.close_to_close),
+            format_vol(realized.parkinson)
+        ));
+    }
+    if let Some(lowest) = metrics
+        .correlations
+        .iter()
+        .min_by(|a, b| a.coefficient.total_cmp(&b.coefficient))
+    {
+        parts.push(format!(
+            "Lowest corr {}h: {} ~ {} {:.2}",
+            lowest.window_hours, lowest.left, lowest.right, lowest.coefficient
+        ));
+    }
+    (!parts.is_empty()).then(|| parts.join(" | "))
+}
+
+fn draw_vol_surface(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    metrics: &MetricsSummary,
+    asset: Option<&AssetMetrics>,
+) {
+    let Some((asset, vol)) = asset.and_then(|asset| Some((asset, asset.vol.as_ref()?))) else {
+        return;
+    };
+    let format_vol = |value: Option<f64>| {
+        value
+            .map(|value| format!("{:+.1}", value * 100.0))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let rows = vol
+        .expiries
+        .iter()
+        .take(MAX_VOL_ROWS)
+        .map(|expiry| {
+            Row::new(vec![
+                Cell::from(expiry.label.clone()),
+                Cell::from(format!("{:.0}", expiry.days_to_expiry)),
+                Cell::from(format!("{:.1}", expiry.atm_iv * 100.0)),
+                Cell::from(format_vol(expiry.risk_reversal_25d)),
+                Cell::from(format_vol(expiry.butterfly_25d)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Length(5),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(7),
+    ];
+    let mut title = format!("{} Vol Surface", asset.asset);
+    if let Some(dvol) = vol.volatility_index {
+        title.push_str(&format!(" | DVOL {:.1}", dvol * 100.0));
+    }
+    if let Some((realized, annualized)) = reference_realized_vol(metrics, &asset.asset) {
+        title.push_str(&format!(
+            " | RV {}h {:.1}",
+            realized.window_hours,
+            annualized * 100.0
+        ));
+    }
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(vec!["Expiry", "Days", "ATM IV", "25d RR", "25d BF"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(theme.block().title(title))
+        .column_spacing(1);
+    frame.render_widget(table, area);
+}
+
+fn draw_term_structure(frame: &mut Frame, area: Rect, theme: &Theme, metrics: &AssetMetrics) {
+    let mut venues: Vec<&str> = Vec::new();
+    for point in &metrics.term_structure {
+        if !venues.contains(&point.venue.as_str()) {
+            venues.push(&point.venue);
         }
+    }
 
-        alert_lines.push(Line::from(""));
-        alert_lines.push(Line::from("Press 'q' or Esc to exit"));
+    let curves: Vec<Vec<(f64, f64)>> = venues
+        .iter()
+        .map(|venue| {
+            metrics
+                .term_structure
+                .iter()
+                .filter(|point| point.venue == *venue)
+                .map(|point| (point.days_to_expiry, point.annualized_basis * 100.0))
+                .collect()
+        })
+        .collect();
+
+    let max_days = metrics
+        .term_structure
+        .iter()
+        .map(|point| point.days_to_expiry)
+        .fold(1.0_f64, f64::max);
+    let funding = metrics.annualized_perp_funding * 100.0;
+    let funding_line = [(0.0, funding), (max_days, funding)];
+
+    let (mut min_y, mut max_y) = curves
+        .iter()
+        .flatten()
+        .map(|(_, y)| *y)
+        .fold((funding, funding), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let pad = ((max_y - min_y) * 0.1).max(0.5);
+    min_y -= pad;
+    max_y += pad;
 
-        let alerts = Paragraph::new(alert_lines).block(
-            Block::default()
-                .title("Alerts & Shortcuts")
-                .borders(Borders::ALL),
+    let mut datasets = vec![
+        Dataset::default()
+            .name("Perp funding")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(theme.series(0))
+            .data(&funding_line),
+    ];
+    for (idx, (venue, curve)) in venues.iter().zip(&curves).enumerate() {
+        datasets.push(
+            Dataset::default()
+                .name(venue.to_string())
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(theme.series(idx + 1))
+                .data(curve),
         );
-        let alerts_chunk = chunks[chunks.len() - 1];
-        frame.render_widget(alerts, alerts_chunk);
     }
+
+    let chart = Chart::new(datasets)
+        .block(
+            theme
+                .block()
+                .title(format!("{} Term Structure (ann. basis %)", metrics.asset)),
+        )
+        .x_axis(
+            Axis::default()
+                .title("Days")
+                .bounds([0.0, max_days])
+                .labels(vec![
+                    Span::raw("0"),
+                    Span::raw(format!("{:.0}", max_days / 2.0)),
+                    Span::raw(format!("{:.0}", max_days)),
+                ]),
+        )
+        .y_axis(Axis::default().bounds([min_y, max_y]).labels(vec![
+            Span::raw(format!("{:.1}", min_y)),
+            Span::raw(format!("{:.1}", (min_y + max_y) / 2.0)),
+            Span::raw(format!("{:.1}", max_y)),
+        ]));
+    frame.render_widget(chart, area);
 }
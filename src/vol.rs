@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::data::{OptionChain, OptionQuote};
+
+const TARGET_DELTA: f64 = 0.25;
+
+/// Smile metrics for one option expiry. Volatilities are fractions
+/// (0.55 = 55 vol); risk reversal and butterfly are in the same units.
+#[derive(Debug, Clone)]
+pub struct ExpiryVol {
+    pub expiry: DateTime<Utc>,
+    pub label: String,
+    pub days_to_expiry: f64,
+    pub forward: f64,
+    pub atm_iv: f64,
+    /// 25-delta call IV minus 25-delta put IV.
+    pub risk_reversal_25d: Option<f64>,
+    /// Average 25-delta wing IV minus ATM IV.
+    pub butterfly_25d: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VolSurface {
+    pub venue: String,
+    pub volatility_index: Option<f64>,
+    /// Expiries ordered nearest first.
+    pub expiries: Vec<ExpiryVol>,
+}
+
+impl VolSurface {
+    pub fn from_chain(chain: &OptionChain, now: DateTime<Utc>) -> Self {
+        let mut by_expiry: BTreeMap<DateTime<Utc>, Vec<&OptionQuote>> = BTreeMap::new();
+        for quote in chain.quotes.iter().filter(|quote| quote.expiry > now) {
+            by_expiry.entry(quote.expiry).or_default().push(quote);
+        }
+
+        Self {
+            venue: chain.venue.clone(),
+            volatility_index: chain.volatility_index,
+            expiries: by_expiry
+                .into_iter()
+                .filter_map(|(expiry, quotes)| expiry_vol(expiry, &quotes, now))
+                .collect(),
+        }
+    }
+
+    /// Front expiry, or the one whose label (e.g. `27JUN25`) matches.
+    pub fn expiry(&self, label: Option<&str>) -> Option<&ExpiryVol> {
+        match label {
+            Some(label) => self
+                .expiries
+                .iter()
+                .find(|expiry| expiry.label.eq_ignore_ascii_case(label)),
+            None => self.expiries.first(),
+        }
+    }
+}
+
+fn expiry_vol(
+    expiry: DateTime<Utc>,
+    quotes: &[&OptionQuote],
+    now: DateTime<Utc>,
+) -> Option<ExpiryVol> {
+    let years = (expiry - now).num_seconds() as f64 / (365.0 * 86_400.0);
+    if years <= 0.0 {
+        return None;
+    }
+    let forward = quotes
+        .iter()
+        .map(|quote| quote.underlying_price)
+        .sum::<f64>()
+        / quotes.len() as f64;
+
+    let atm_strike = quotes
+        .iter()
+        .map(|quote| quote.strike)
+        .min_by(|a, b| (a - forward).abs().total_cmp(&(b - forward).abs()))?;
+    let atm_quotes: Vec<f64> = quotes
+        .iter()
+        .filter(|quote| quote.strike == atm_strike)
+        .map(|quote| quote.mark_iv)
+        .collect();
+    let atm_iv = atm_quotes.iter().sum::<f64>() / atm_quotes.len() as f64;
+
+    let call_25 = wing_iv(quotes, true, forward, years);
+    let put_25 = wing_iv(quotes, false, forward, years);
+    let (risk_reversal_25d, butterfly_25d) = match (call_25, put_25) {
+        (Some(call), Some(put)) => (Some(call - put), Some((call + put) / 2.0 - atm_iv)),
+        _ => (None, None),
+    };
+
+    Some(ExpiryVol {
+        expiry,
+        label: expiry.format("%-d%b%y").to_string().to_uppercase(),
+        days_to_expiry: years * 365.0,
+        forward,
+        atm_iv,
+        risk_reversal_25d,
+        butterfly_25d,
+    })
+}
+
+/// IV of the call (or put) whose Black-76 delta sits closest to 25.
+fn wing_iv(quotes: &[&OptionQuote], is_call: bool, forward: f64, years: f64) -> Option<f64> {
+    quotes
+        .iter()
+        .filter(|quote| quote.is_call == is_call && quote.mark_iv > 0.0)
+        .map(|quote| {
+            let delta = black_delta(forward, quote.strike, quote.mark_iv, years, is_call).abs();
+            ((delta - TARGET_DELTA).abs(), quote.mark_iv)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, iv)| iv)
+}
+
+fn black_delta(forward: f64, strike: f64, vol: f64, years: f64, is_call: bool) -> f64 {
+    let sigma_t = vol * years.sqrt();
+    let d1 = ((forward / strike).ln() + 0.5 * sigma_t * sigma_t) / sigma_t;
+    if is_call {
+        normal_cdf(d1)
+    } else {
+        normal_cdf(d1) - 1.0
+    }
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz & Stegun 7.1.26; accurate to ~1.5e-7, ample for delta buckets.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn quarter_out() -> DateTime<Utc> {
+        now() + chrono::Duration::seconds((0.25 * 365.0 * 86_400.0) as i64)
+    }
+
+    fn quote(expiry: DateTime<Utc>, strike: f64, is_call: bool, mark_iv: f64) -> OptionQuote {
+        OptionQuote {
+            symbol: format!("BTC-{}-{}", strike, if is_call { "C" } else { "P" }),
+            expiry,
+            strike,
+            is_call,
+            mark_iv,
+            underlying_price: 100.0,
+            open_interest: None,
+        }
+    }
+
+    fn chain(quotes: Vec<OptionQuote>) -> OptionChain {
+        OptionChain {
+            venue: "Deribit".to_string(),
+            asset: "BTC".to_string(),
+            quotes,
+            volatility_index: Some(0.5),
+            fetched_at: now(),
+        }
+    }
+
+    #[test]
+    fn black_delta_matches_reference_values() {
+        let cases = [
+            (100.0, 100.0, 0.5, 1.0, 0.598_706_3),
+            (100.0, 110.0, 0.2, 0.5, 0.273_176_5),
+            (60_000.0, 50_000.0, 0.6, 0.25, 0.775_696_2),
+        ];
+        for (forward, strike, vol, years, call_delta) in cases {
+            let call = black_delta(forward, strike, vol, years, true);
+            let put = black_delta(forward, strike, vol, years, false);
+            assert!(
+                (call - call_delta).abs() < 1e-6,
+                "call {call} vs {call_delta}"
+            );
+            assert!((put - (call_delta - 1.0)).abs() < 1e-6, "put {put}");
+        }
+    }
+
+    #[test]
+    fn smile_picks_the_wings_nearest_25_delta() {
+        let expiry = quarter_out();
+        let surface = VolSurface::from_chain(
+            &chain(vec![
+                quote(expiry, 100.0, true, 0.50),
+                quote(expiry, 110.0, true, 0.52),
+                quote(expiry, 120.0, true, 0.55),
+                quote(expiry, 130.0, true, 0.60),
+                quote(expiry, 80.0, false, 0.62),
+                quote(expiry, 90.0, false, 0.58),
+                quote(expiry, 100.0, false, 0.52),
+            ]),
+            now(),
+        );
+
+        let smile = &surface.expiries[0];
+        assert_eq!(smile.label, "31AUG24");
+        assert!((smile.days_to_expiry - 91.25).abs() < 1e-9);
+        assert_eq!(smile.forward, 100.0);
+        // Both 100-strike quotes are ATM and are averaged.
+        assert!((smile.atm_iv - 0.51).abs() < 1e-12);
+        // Call wing: 130 (delta 0.234) beats 120 (0.300); put wing: 90 (-0.306) beats 80 (-0.191).
+        assert!((smile.risk_reversal_25d.unwrap() - (0.60 - 0.58)).abs() < 1e-12);
+        assert!((smile.butterfly_25d.unwrap() - (0.59 - 0.51)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn one_sided_smiles_have_no_skew() {
+        let expiry = quarter_out();
+        let surface = VolSurface::from_chain(
+            &chain(vec![
+                quote(expiry, 100.0, true, 0.50),
+                quote(expiry, 130.0, true, 0.60),
+            ]),
+            now(),
+        );
+        let smile = &surface.expiries[0];
+        assert_eq!(smile.atm_iv, 0.50);
+        assert!(smile.risk_reversal_25d.is_none());
+        assert!(smile.butterfly_25d.is_none());
+    }
+
+    #[test]
+    fn expiries_are_ordered_and_expired_ones_dropped() {
+        let front = now() + chrono::Duration::days(7);
+        let surface = VolSurface::from_chain(
+            &chain(vec![
+                quote(quarter_out(), 100.0, true, 0.55),
+                quote(now() - chrono::Duration::days(1), 100.0, true, 0.90),
+                quote(front, 100.0, true, 0.45),
+            ]),
+            now(),
+        );
+        let labels: Vec<&str> = surface.expiries.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["8JUN24", "31AUG24"]);
+        assert_eq!(surface.expiry(None).unwrap().atm_iv, 0.45);
+        assert_eq!(surface.expiry(Some("31aug24")).unwrap().atm_iv, 0.55);
+        assert!(surface.expiry(Some("27DEC24")).is_none());
+    }
+}